@@ -1,14 +1,16 @@
-use sync_http::{query::Query, server::Server, ServerResult};
+use sync_http::{request::Request, response::Response, server::Server, ServerResult};
 
 fn main() -> ServerResult<()> {
     Server::create()
         .ip_address("192.168.126.128".into())
         .port(8080)
         .get("*", &index)?
-        .bind()
+        .bind()?
+        .join()?;
+    Ok(())
 }
 
-fn index(_uri: String, _query: Query) -> ServerResult<String> {
+fn index(_request: Request) -> ServerResult<Response> {
     Ok(r#"<html>
         <head>
             <title>Hello, world!</title>
@@ -19,5 +21,5 @@ fn index(_uri: String, _query: Query) -> ServerResult<String> {
         </body>
         </html>
         "#
-    .to_string())
+    .into())
 }
@@ -0,0 +1,38 @@
+use sync_http::{
+    query::FromQuery, request::Request, response::Response, server::Server, ServerResult,
+};
+
+#[derive(FromQuery)]
+struct Search {
+    q: String,
+    page: Option<u32>,
+    #[query(rename = "tag")]
+    tags: Vec<String>,
+}
+
+fn main() -> ServerResult<()> {
+    Server::create().get("search", &search)?.bind()?.join()?;
+    Ok(())
+}
+
+// A missing `q` or a `page` which is not a number is answered with a
+// `400 Bad Request`, e.g. `/search?q=rust&page=2&tag=http&tag=sync`
+fn search(request: Request) -> ServerResult<Response> {
+    let search: Search = request.query.extract()?;
+    Ok(format!(
+        r#"<html>
+        <head>
+            <title>Search example</title>
+        </head>
+        <body>
+            <h1>Results for "{}"</h1>
+            <p>Page {} with the tags {:?}</p>
+        </body>
+        </html>
+        "#,
+        search.q,
+        search.page.unwrap_or(1),
+        search.tags
+    )
+    .into())
+}
@@ -1,39 +1,48 @@
-use std::sync::mpsc::{channel, Sender};
-use sync_http::{query::Query, server::Server, ServerResult};
+use std::sync::OnceLock;
+use sync_http::{
+    request::Request,
+    response::Response,
+    server::{Server, ServerHandle},
+    ServerResult,
+};
 
-static mut SHUTDOWN: Option<Sender<()>> = None;
+static SERVER: OnceLock<ServerHandle> = OnceLock::new();
 
 fn main() -> ServerResult<()> {
-    let (send, recv) = channel();
-    unsafe {
-        SHUTDOWN = Some(send);
-    }
-    Server::create()
+    let server = Server::create()
         .ip_address("192.168.126.128".into())
-        .get("close", &shutdown)?
+        .post("close", &shutdown)?
         .get("*", &index)?
-        .shutdown(recv)
-        .bind()
+        .bind()?;
+    SERVER.get_or_init(|| server).join()?;
+    Ok(())
 }
 
-fn index(_uri: String, _query: Query) -> ServerResult<String> {
+fn index(_request: Request) -> ServerResult<Response> {
     Ok(r#"<html>
         <head>
             <title>Shutdown example</title>
         </head>
         <body>
-            <form action="./close">
+            <form action="./close" method="post">
+                <input type="hidden" name="confirm" value="yes" />
                 <input type="submit" value="Shut Server Down" />
             </form>
         </body>
         </html>
         "#
-    .to_string())
+    .into())
 }
 
-fn shutdown(_uri: String, _query: Query) -> ServerResult<String> {
-    if let Some(shutdown) = unsafe { SHUTDOWN.clone() } {
-        shutdown.send(())?;
+fn shutdown(request: Request) -> ServerResult<Response> {
+    let confirmed = request
+        .form()
+        .is_some_and(|form| form.get("confirm") == Some("yes"));
+    if !confirmed {
+        return Ok(Response::redirect("/"));
+    }
+    if let Some(server) = SERVER.get() {
+        server.shutdown();
     }
     Ok(r#"<html>
         <head>
@@ -44,5 +53,5 @@ fn shutdown(_uri: String, _query: Query) -> ServerResult<String> {
         </body>
         </html>
         "#
-    .to_string())
+    .into())
 }
@@ -1,35 +1,36 @@
 use std::{
-    sync::mpsc::channel,
+    sync::atomic::{AtomicUsize, Ordering},
     thread::{self},
     time::Duration,
 };
-use sync_http::{query::Query, server::Server, ServerResult};
+use sync_http::{request::Request, response::Response, server::Server, ServerResult};
 
-static mut COUNTDOWN: usize = 10;
+static COUNTDOWN: AtomicUsize = AtomicUsize::new(10);
 
 fn main() -> ServerResult<()> {
-    let (send, recv) = channel();
+    let server = Server::create()
+        .ip_address("192.168.126.128".into())
+        .get("*", &index)?
+        .shutdown_timeout(Duration::from_secs(5))
+        .bind()?;
+    let handle = server.clone();
     thread::spawn(move || {
         loop {
             thread::sleep(Duration::from_secs(1));
-            unsafe {
-                COUNTDOWN -= 1;
-            }
-            if unsafe { COUNTDOWN } == 0 {
+            let countdown = COUNTDOWN.fetch_sub(1, Ordering::SeqCst) - 1;
+            if countdown == 0 {
                 break;
             }
-            println!("Shutting down in {} seconds.", unsafe { COUNTDOWN });
+            println!("Shutting down in {countdown} seconds.");
         }
-        send.send(()).unwrap();
+        handle.shutdown();
     });
-    Server::create()
-        .ip_address("192.168.126.128".into())
-        .get("*", &index)?
-        .shutdown(recv)
-        .bind()
+    let report = server.join()?;
+    println!("Shut down, {} connections were cut off.", report.cut_off);
+    Ok(())
 }
 
-fn index(_uri: String, _query: Query) -> ServerResult<String> {
+fn index(_request: Request) -> ServerResult<Response> {
     Ok(format!(
         r#"<html>
         <head>
@@ -40,6 +41,7 @@ fn index(_uri: String, _query: Query) -> ServerResult<String> {
         </body>
         </html>
         "#,
-        unsafe { COUNTDOWN }
-    ))
+        COUNTDOWN.load(Ordering::SeqCst)
+    )
+    .into())
 }
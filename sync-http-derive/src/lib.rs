@@ -0,0 +1,104 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    parse_macro_input, Data, DeriveInput, Fields, GenericArgument, LitStr, PathArguments, Type,
+};
+
+/// Derives `sync_http::query::FromQuery` for a struct with named fields,
+/// where each field is looked up by its name in the query:
+/// - `Option<T>` fields are optional
+/// - `Vec<T>` fields collect every value of a repeated key
+/// - any other field is required
+///
+/// Every value is converted with `FromStr`, and a field can be looked up
+/// under a different key with `#[query(rename = "key")]`.
+#[proc_macro_derive(FromQuery, attributes(query))]
+pub fn derive_from_query(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match from_query(input) {
+        Ok(output) => output.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn from_query(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => Err(syn::Error::new_spanned(
+                &input.ident,
+                "FromQuery can only be derived for structs with named fields",
+            ))?,
+        },
+        _ => Err(syn::Error::new_spanned(
+            &input.ident,
+            "FromQuery can only be derived for structs",
+        ))?,
+    };
+
+    let mut extracted = vec![];
+    for field in fields {
+        let ident = field
+            .ident
+            .as_ref()
+            .expect("Named fields always have an ident");
+        let mut key = ident.to_string();
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("query"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    key = meta.value()?.parse::<LitStr>()?.value();
+                    Ok(())
+                } else {
+                    Err(meta.error("Unknown query attribute, expected `rename`"))
+                }
+            })?;
+        }
+
+        let extract = match wrapper(&field.ty) {
+            Some("Option") => quote!(::sync_http::query::optional),
+            Some("Vec") => quote!(::sync_http::query::repeated),
+            _ => quote!(::sync_http::query::required),
+        };
+        extracted.push(quote!(#ident: #extract(query, #key)?));
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::sync_http::query::FromQuery for #name #ty_generics #where_clause {
+            fn from_query(
+                query: &::sync_http::query::Query,
+            ) -> ::std::result::Result<Self, ::sync_http::errors::QueryError> {
+                ::std::result::Result::Ok(Self {
+                    #(#extracted,)*
+                })
+            }
+        }
+    })
+}
+
+/// Finds the name of the type wrapping a field, for `Option<T>` or `Vec<T>`.
+fn wrapper(ty: &Type) -> Option<&'static str> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    if args.args.len() != 1 || !matches!(args.args[0], GenericArgument::Type(_)) {
+        return None;
+    }
+    if segment.ident == "Option" {
+        Some("Option")
+    } else if segment.ident == "Vec" {
+        Some("Vec")
+    } else {
+        None
+    }
+}
@@ -1,26 +1,92 @@
 use std::{error::Error, fmt::Display};
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub enum RequestError {
     TooManyValues,
     BadMethod,
     BadProtocol,
+    BadUri,
+    BadHeader,
+    BadContentLength,
+    BadChunk,
+    UnsupportedTransferEncoding,
+    Incomplete,
+    RequestLineTooLong,
+    HeadersTooLarge,
+    TooManyHeaders,
+    BodyTooLarge,
+}
+
+/// A query failed to convert into a typed value.
+#[derive(Debug, PartialEq, Eq)]
+pub enum QueryError {
+    Missing(String),
+    Invalid { key: String, value: String },
+}
+
+/// A multipart body was malformed or went over its limits.
+#[derive(Debug, PartialEq, Eq)]
+pub enum MultipartError {
+    Malformed,
+    PartTooLarge,
+    TooLarge,
 }
 
 #[derive(Debug)]
 pub struct FailedToCompileRoute;
 
+/// A status code which isn't the three digits a status line needs.
+#[derive(Debug, PartialEq, Eq)]
+pub struct InvalidStatusCode(pub u16);
+
+/// A handler panicked, holding the message it panicked with.
+#[derive(Debug)]
+pub struct HandlerPanicked(pub String);
+
 impl Display for RequestError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{self:?}")
     }
 }
 
+impl Display for QueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QueryError::Missing(key) => write!(f, "Missing query parameter `{key}`"),
+            QueryError::Invalid { key, value } => {
+                write!(f, "Invalid value `{value}` for query parameter `{key}`")
+            }
+        }
+    }
+}
+
+impl Display for MultipartError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
 impl Display for FailedToCompileRoute {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{self:?}")
     }
 }
 
+impl Display for InvalidStatusCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid status code {}", self.0)
+    }
+}
+
+impl Display for HandlerPanicked {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Handler panicked: {}", self.0)
+    }
+}
+
 impl Error for RequestError {}
+impl Error for QueryError {}
+impl Error for MultipartError {}
 impl Error for FailedToCompileRoute {}
+impl Error for InvalidStatusCode {}
+impl Error for HandlerPanicked {}
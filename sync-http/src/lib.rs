@@ -2,8 +2,16 @@ use std::error::Error;
 
 pub mod errors;
 pub mod mime;
+pub mod multipart;
 pub mod query;
 pub mod request;
+pub mod response;
 pub mod server;
 
-pub type ServerResult<T> = Result<T, Box<dyn Error>>;
+// Lets the derive macros' `::sync_http` paths resolve in this crate's tests
+#[cfg(all(test, feature = "derive"))]
+extern crate self as sync_http;
+
+/// Errors are `Send` and `Sync` so that they can be passed back from the
+/// threads the server runs on.
+pub type ServerResult<T> = Result<T, Box<dyn Error + Send + Sync>>;
@@ -6,6 +6,7 @@ pub enum MediaType {
     Font,
     Image,
     Model,
+    Multipart,
     Text,
     Video,
 }
@@ -19,6 +20,7 @@ impl MediaType {
             "font" => MediaType::Font,
             "image" => MediaType::Image,
             "model" => MediaType::Model,
+            "multipart" => MediaType::Multipart,
             "text" => MediaType::Text,
             "video" => MediaType::Video,
             _ => None?,
@@ -5,6 +5,7 @@ pub enum MimeType {
     All,
 
     // Application Types
+    FormUrlEncoded,
     JSON,
     OctetStream,
     XHTML,
@@ -22,6 +23,9 @@ pub enum MimeType {
     Webp,
 
     // TODO: Model Types
+    // Multipart Types
+    FormData,
+
     // Text Types
     CSS,
     HTML,
@@ -34,6 +38,7 @@ impl MimeType {
     pub fn parse(mime_type: &str) -> Option<Self> {
         Some(match mime_type {
             "*" => MimeType::All,
+            "x-www-form-urlencoded" => MimeType::FormUrlEncoded,
             "json" => MimeType::JSON,
             "octet-stream" => MimeType::OctetStream,
             "xhtml" => MimeType::XHTML,
@@ -45,6 +50,7 @@ impl MimeType {
             "png" => MimeType::PNG,
             "svg" => MimeType::SVG,
             "webp" => MimeType::Webp,
+            "form-data" => MimeType::FormData,
             "css" => MimeType::CSS,
             "html" => MimeType::HTML,
             "javascript" => MimeType::Javascript,
@@ -56,9 +62,11 @@ impl MimeType {
     pub fn associated_media(&self) -> MediaType {
         match self {
             MimeType::All => MediaType::All,
-            MimeType::JSON | MimeType::OctetStream | MimeType::XHTML | MimeType::XML => {
-                MediaType::Application
-            }
+            MimeType::FormUrlEncoded
+            | MimeType::JSON
+            | MimeType::OctetStream
+            | MimeType::XHTML
+            | MimeType::XML => MediaType::Application,
             MimeType::APNG
             | MimeType::AVIF
             | MimeType::GIF
@@ -66,6 +74,7 @@ impl MimeType {
             | MimeType::PNG
             | MimeType::SVG
             | MimeType::Webp => MediaType::Image,
+            MimeType::FormData => MediaType::Multipart,
             MimeType::CSS | MimeType::HTML | MimeType::Javascript | MimeType::Plain => {
                 MediaType::Text
             }
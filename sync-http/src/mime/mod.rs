@@ -10,8 +10,16 @@ pub use suffix::*;
 #[cfg(test)]
 mod tests;
 
-#[derive(PartialEq, Clone, Copy)]
-pub struct ContentType(pub MediaType, pub MimeType, pub MimeSuffix, pub f64);
+/// A media type with its priority and any other parameters, such as
+/// `text/html; charset=utf-8` or `multipart/form-data; boundary=...`.
+#[derive(PartialEq, Clone)]
+pub struct ContentType(
+    pub MediaType,
+    pub MimeType,
+    pub MimeSuffix,
+    pub f64,
+    pub Vec<(String, String)>,
+);
 
 impl ContentType {
     pub fn parse_many(content_types: String) -> Option<Vec<Self>> {
@@ -31,28 +39,52 @@ impl ContentType {
     }
 
     pub fn parse(content_type: String) -> Option<Self> {
-        let parts: Vec<&str> = content_type.split(";q=").collect();
-        let priority: f64 = if parts.len() > 1 {
-            parts[1].parse().ok()?
-        } else {
-            1.0
-        };
-        let media: Vec<&str> = parts[0].split('/').collect();
-        let mime: Vec<&str> = media[1].split('+').collect();
+        let (media_range, parameters) = content_type
+            .split_once(';')
+            .unwrap_or((content_type.as_str(), ""));
+        let mut priority: f64 = 1.0;
+        let mut other_parameters = vec![];
+        for (name, value) in parse_parameters(parameters) {
+            if name == "q" {
+                priority = value.parse().ok()?;
+            } else {
+                other_parameters.push((name, value));
+            }
+        }
+        let (media, mime) = media_range.trim().split_once('/')?;
+        let mime: Vec<&str> = mime.split('+').collect();
         let suffix = if mime.len() > 1 {
             MimeSuffix::parse(mime[1])?
         } else {
             MimeSuffix::None
         };
         Self(
-            MediaType::parse(media[0])?,
+            MediaType::parse(media)?,
             MimeType::parse(mime[0])?,
             suffix,
             priority,
+            other_parameters,
         )
         .validate()
     }
 
+    /// Gets the value of a parameter other than the priority, parameter
+    /// names are case insensitive.
+    pub fn parameter(&self, name: &str) -> Option<&str> {
+        self.4
+            .iter()
+            .find(|(parameter, _)| parameter.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn charset(&self) -> Option<&str> {
+        self.parameter("charset")
+    }
+
+    pub fn boundary(&self) -> Option<&str> {
+        self.parameter("boundary")
+    }
+
     pub fn validate(self) -> Option<Self> {
         if self.0 == MediaType::All {
             return Some(self);
@@ -68,13 +100,74 @@ impl ContentType {
 
 impl Debug for ContentType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let parameters: String = self
+            .4
+            .iter()
+            .map(|(name, value)| format!(";{name}={value}"))
+            .collect();
         match self.2 {
-            MimeSuffix::None => write!(f, "ContentType({:?}/{:?};q={})", self.0, self.1, self.3),
+            MimeSuffix::None => write!(
+                f,
+                "ContentType({:?}/{:?};q={}{parameters})",
+                self.0, self.1, self.3
+            ),
             _ => write!(
                 f,
-                "ContentType({:?}/{:?}+{:?};q={})",
+                "ContentType({:?}/{:?}+{:?};q={}{parameters})",
                 self.0, self.1, self.2, self.3
             ),
         }
     }
 }
+
+/// Parses `;` separated parameters, such as `charset=utf-8; q=0.5`, where
+/// values may be quoted strings. Names are lowercased as they are case
+/// insensitive, and any malformed parameters are skipped.
+pub fn parse_parameters(parameters: &str) -> Vec<(String, String)> {
+    let mut parsed = vec![];
+    let mut rest = parameters;
+    loop {
+        rest = rest.trim_start_matches([';', ' ', '\t']);
+        if rest.is_empty() {
+            break;
+        }
+        let name_end = rest.find(['=', ';']).unwrap_or(rest.len());
+        let name = rest[..name_end].trim().to_lowercase();
+        rest = &rest[name_end..];
+        let Some(value) = rest.strip_prefix('=') else {
+            continue;
+        };
+        let value = value.trim_start_matches([' ', '\t']);
+        let (value, remaining) = match value.strip_prefix('"') {
+            Some(quoted) => unquote(quoted),
+            None => {
+                let end = value.find(';').unwrap_or(value.len());
+                (value[..end].trim().to_string(), &value[end..])
+            }
+        };
+        if !name.is_empty() {
+            parsed.push((name, value));
+        }
+        rest = remaining;
+    }
+    parsed
+}
+
+/// Reads a quoted string up to its closing quote, returning the string
+/// and whatever follows it.
+fn unquote(quoted: &str) -> (String, &str) {
+    let mut value = String::new();
+    let mut chars = quoted.char_indices();
+    while let Some((i, ch)) = chars.next() {
+        match ch {
+            '"' => return (value, &quoted[i + 1..]),
+            '\\' => {
+                if let Some((_, escaped)) = chars.next() {
+                    value.push(escaped);
+                }
+            }
+            _ => value.push(ch),
+        }
+    }
+    (value, "")
+}
@@ -3,6 +3,7 @@ macro_rules! check_new_mimes {
     ($v:expr) => {
         match $v {
             MimeType::All
+            | MimeType::FormUrlEncoded
             | MimeType::JSON
             | MimeType::OctetStream
             | MimeType::XHTML
@@ -14,6 +15,7 @@ macro_rules! check_new_mimes {
             | MimeType::PNG
             | MimeType::SVG
             | MimeType::Webp
+            | MimeType::FormData
             | MimeType::CSS
             | MimeType::HTML
             | MimeType::Javascript
@@ -31,7 +33,8 @@ fn parse_all_types() {
             MediaType::All,
             MimeType::All,
             MimeSuffix::None,
-            1.0
+            1.0,
+            vec![],
         ))
     );
 }
@@ -44,9 +47,13 @@ fn mismatched_type() {
 
 #[test]
 fn parse_applications() {
-    const NUM_TYPES: usize = 2;
-    let app_types: [_; NUM_TYPES] = ["octet-stream", "json"];
-    let mime_types: [_; NUM_TYPES] = [MimeType::OctetStream, MimeType::JSON];
+    const NUM_TYPES: usize = 3;
+    let app_types: [_; NUM_TYPES] = ["octet-stream", "json", "x-www-form-urlencoded"];
+    let mime_types: [_; NUM_TYPES] = [
+        MimeType::OctetStream,
+        MimeType::JSON,
+        MimeType::FormUrlEncoded,
+    ];
 
     // Will cause error if more mime types are added
     check_new_mimes!(mime_types[0]);
@@ -59,7 +66,8 @@ fn parse_applications() {
                 MediaType::Application,
                 mime_types[i],
                 MimeSuffix::None,
-                1.0
+                1.0,
+                vec![],
             ))
         );
     }
@@ -90,12 +98,29 @@ fn parse_images() {
                 MediaType::Image,
                 mime_types[i],
                 MimeSuffix::None,
-                1.0
+                1.0,
+                vec![],
             ))
         );
     }
 }
 
+#[test]
+fn parse_multipart() {
+    let ct = ContentType::parse("multipart/form-data".into());
+    assert_eq!(
+        ct,
+        Some(ContentType(
+            MediaType::Multipart,
+            MimeType::FormData,
+            MimeSuffix::None,
+            1.0,
+            vec![],
+        ))
+    );
+    check_new_mimes!(MimeType::FormData);
+}
+
 #[test]
 fn parse_text() {
     const NUM_TYPES: usize = 4;
@@ -118,8 +143,62 @@ fn parse_text() {
                 MediaType::Text,
                 mime_types[i],
                 MimeSuffix::None,
-                1.0
+                1.0,
+                vec![],
             ))
         );
     }
 }
+
+#[test]
+fn parse_parameters() {
+    const NUM_TESTS: usize = 5;
+    let content_types: [&str; NUM_TESTS] = [
+        "text/html;q=0.8",
+        "text/html; Charset=utf-8",
+        " application/x-www-form-urlencoded ; charset=UTF-8 ; q=0.5",
+        "multipart/form-data; boundary=\"a; \\\"b\\\"\"; empty",
+        "text",
+    ];
+    let test_vals: [Option<_>; NUM_TESTS] = [
+        Some(ContentType(
+            MediaType::Text,
+            MimeType::HTML,
+            MimeSuffix::None,
+            0.8,
+            vec![],
+        )),
+        Some(ContentType(
+            MediaType::Text,
+            MimeType::HTML,
+            MimeSuffix::None,
+            1.0,
+            vec![("charset".into(), "utf-8".into())],
+        )),
+        Some(ContentType(
+            MediaType::Application,
+            MimeType::FormUrlEncoded,
+            MimeSuffix::None,
+            0.5,
+            vec![("charset".into(), "UTF-8".into())],
+        )),
+        Some(ContentType(
+            MediaType::Multipart,
+            MimeType::FormData,
+            MimeSuffix::None,
+            1.0,
+            vec![("boundary".into(), "a; \"b\"".into())],
+        )),
+        None,
+    ];
+
+    for i in 0..NUM_TESTS {
+        let ct = ContentType::parse(content_types[i].into());
+        assert_eq!(ct, test_vals[i]);
+    }
+
+    let ct = test_vals[1].clone().unwrap();
+    assert_eq!(ct.charset(), Some("utf-8"));
+    assert_eq!(ct.parameter("CHARSET"), Some("utf-8"));
+    assert_eq!(ct.boundary(), None);
+}
@@ -0,0 +1,201 @@
+use crate::errors::MultipartError;
+use crate::mime::{parse_parameters, ContentType};
+use std::borrow::Cow;
+
+#[cfg(test)]
+mod tests;
+
+/// The limits on the size of a multipart body, by default each part may
+/// be up to 4 MiB and all of the parts together up to 8 MiB. The body has
+/// already been read by the time it is parsed, so these only narrow down
+/// the request's own body size limit, which bounds how much is read, and
+/// have no effect when set above it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MultipartLimits {
+    pub part_size: usize,
+    pub total_size: usize,
+}
+
+impl Default for MultipartLimits {
+    fn default() -> Self {
+        Self {
+            part_size: 4 * 1024 * 1024,
+            total_size: 8 * 1024 * 1024,
+        }
+    }
+}
+
+/// A `multipart/form-data` body, which parses each boundary delimited part
+/// out of the already read body as it is iterated over.
+pub struct Multipart<'a> {
+    body: &'a [u8],
+    delimiter: Vec<u8>,
+    position: Option<usize>,
+    limits: MultipartLimits,
+    total_size: usize,
+    finished: bool,
+}
+
+/// A single part of a multipart body, borrowing its data from the body.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Part<'a> {
+    pub name: Option<String>,
+    pub filename: Option<String>,
+    /// The parsed `Content-Type`, which is `None` for types this crate
+    /// doesn't know, as well as for parts without one.
+    pub content_type: Option<ContentType>,
+    /// The `Content-Type` exactly as it was sent, for any type.
+    pub raw_content_type: Option<String>,
+    pub headers: Vec<(String, String)>,
+    pub data: &'a [u8],
+}
+
+impl<'a> Multipart<'a> {
+    pub fn new(body: &'a [u8], boundary: &str) -> Self {
+        Self {
+            body,
+            delimiter: format!("--{boundary}").into_bytes(),
+            position: None,
+            limits: MultipartLimits::default(),
+            total_size: 0,
+            finished: false,
+        }
+    }
+
+    pub fn limits(mut self, limits: MultipartLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    fn next_part(&mut self) -> Result<Option<Part<'a>>, MultipartError> {
+        let position = match self.position {
+            Some(position) => position,
+            // Anything before the first delimiter is a preamble to ignore
+            None if self.body.starts_with(&self.delimiter) => 0,
+            None => find(self.body, &[b"\r\n", self.delimiter.as_slice()].concat())
+                .map(|start| start + 2)
+                .ok_or(MultipartError::Malformed)?,
+        };
+        let mut position = position + self.delimiter.len();
+
+        // The final delimiter is followed by `--`, and either may be padded
+        let rest = &self.body[position..];
+        let padding = rest
+            .iter()
+            .take_while(|b| **b == b' ' || **b == b'\t')
+            .count();
+        let rest = &rest[padding..];
+        if rest.starts_with(b"--") {
+            return Ok(None);
+        }
+        if !rest.starts_with(b"\r\n") {
+            return Err(MultipartError::Malformed);
+        }
+        position += padding + 2;
+
+        // Parse out the headers, which may be empty
+        let rest = &self.body[position..];
+        let (head, content_start) = if rest.starts_with(b"\r\n") {
+            ("", position + 2)
+        } else {
+            let end = find(rest, b"\r\n\r\n").ok_or(MultipartError::Malformed)?;
+            let head = std::str::from_utf8(&rest[..end]).map_err(|_| MultipartError::Malformed)?;
+            (head, position + end + 4)
+        };
+        let headers = head
+            .split("\r\n")
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                let (name, value) = line.split_once(':').ok_or(MultipartError::Malformed)?;
+                Ok((name.trim().to_string(), value.trim().to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // The data of the part ends at the next delimiter
+        let end = find(
+            &self.body[content_start..],
+            &[b"\r\n", self.delimiter.as_slice()].concat(),
+        )
+        .ok_or(MultipartError::Malformed)?;
+        if end > self.limits.part_size {
+            return Err(MultipartError::PartTooLarge);
+        }
+        self.total_size += end;
+        if self.total_size > self.limits.total_size {
+            return Err(MultipartError::TooLarge);
+        }
+        self.position = Some(content_start + end + 2);
+
+        Ok(Some(Part::new(
+            headers,
+            &self.body[content_start..content_start + end],
+        )))
+    }
+}
+
+impl<'a> Iterator for Multipart<'a> {
+    type Item = Result<Part<'a>, MultipartError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let part = self.next_part().transpose();
+        // Stop at the final delimiter or the first error
+        if !matches!(part, Some(Ok(_))) {
+            self.finished = true;
+        }
+        part
+    }
+}
+
+impl<'a> Part<'a> {
+    fn new(headers: Vec<(String, String)>, data: &'a [u8]) -> Self {
+        let mut name = None;
+        let mut filename = None;
+        let mut content_type = None;
+        let mut raw_content_type = None;
+        for (header, value) in headers.iter() {
+            // Headers are case insensitive
+            if header.eq_ignore_ascii_case("content-disposition") {
+                // The parameters follow the disposition type, `form-data`
+                let (_, parameters) = value.split_once(';').unwrap_or_default();
+                for (parameter, value) in parse_parameters(parameters) {
+                    match parameter.as_str() {
+                        "name" => name = Some(value),
+                        "filename" => filename = Some(value),
+                        _ => {}
+                    }
+                }
+            } else if header.eq_ignore_ascii_case("content-type") {
+                content_type = ContentType::parse(value.clone());
+                raw_content_type = Some(value.clone());
+            }
+        }
+
+        Self {
+            name,
+            filename,
+            content_type,
+            raw_content_type,
+            headers,
+            data,
+        }
+    }
+
+    /// The data of the part, the returned slice implements `Read` if it is
+    /// to be read from rather than used directly.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+
+    pub fn text(&self) -> Cow<'a, str> {
+        String::from_utf8_lossy(self.data)
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
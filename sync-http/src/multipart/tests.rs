@@ -0,0 +1,107 @@
+use super::*;
+use crate::mime::{MediaType, MimeSuffix, MimeType};
+use crate::request::RequestLimits;
+use std::io::Read;
+
+const BODY: &[u8] = b"This preamble is ignored\r\n\
+    --boundary\r\n\
+    Content-Disposition: form-data; name=\"title\"\r\n\r\n\
+    Hello, world!\r\n\
+    --boundary  \r\n\
+    Content-Disposition: form-data; name=\"upload\"; filename=\"notes \\\"v2\\\".txt\"\r\n\
+    content-type: text/plain\r\n\r\n\
+    first line\r\nsecond line\r\n\
+    --boundary\r\n\
+    \r\n\
+    no headers\r\n\
+    --boundary--\r\n\
+    This epilogue is ignored";
+
+#[test]
+fn parse_parts() {
+    let parts = Multipart::new(BODY, "boundary")
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(parts.len(), 3);
+    assert_eq!(parts[0].name.as_deref(), Some("title"));
+    assert_eq!(parts[0].filename, None);
+    assert_eq!(parts[0].content_type, None);
+    assert_eq!(parts[0].raw_content_type, None);
+    assert_eq!(parts[0].text(), "Hello, world!");
+
+    assert_eq!(parts[1].name.as_deref(), Some("upload"));
+    assert_eq!(parts[1].filename.as_deref(), Some("notes \"v2\".txt"));
+    assert_eq!(
+        parts[1].content_type,
+        Some(ContentType(
+            MediaType::Text,
+            MimeType::Plain,
+            MimeSuffix::None,
+            1.0,
+            vec![],
+        ))
+    );
+    assert_eq!(parts[1].raw_content_type.as_deref(), Some("text/plain"));
+    let mut data = vec![];
+    parts[1].as_bytes().read_to_end(&mut data).unwrap();
+    assert_eq!(data, b"first line\r\nsecond line");
+
+    assert!(parts[2].headers.is_empty());
+    assert_eq!(parts[2].data, b"no headers");
+}
+
+#[test]
+fn unknown_content_type() {
+    let body = b"--boundary\r\n\
+        Content-Disposition: form-data; name=\"report\"; filename=\"report.pdf\"\r\n\
+        Content-Type: application/pdf\r\n\r\n\
+        %PDF-1.7\r\n\
+        --boundary--\r\n";
+    let part = Multipart::new(body, "boundary").next().unwrap().unwrap();
+
+    assert_eq!(part.content_type, None);
+    assert_eq!(part.raw_content_type.as_deref(), Some("application/pdf"));
+    assert_eq!(part.as_bytes(), b"%PDF-1.7");
+}
+
+#[test]
+fn size_limits() {
+    // The whole body is read first, so a larger default could never be hit
+    let defaults = MultipartLimits::default();
+    assert!(defaults.part_size <= defaults.total_size);
+    assert!(defaults.total_size < RequestLimits::default().body_size);
+
+    let part_limited = Multipart::new(BODY, "boundary").limits(MultipartLimits {
+        part_size: 20,
+        total_size: 1024,
+    });
+    let total_limited = Multipart::new(BODY, "boundary").limits(MultipartLimits {
+        part_size: 1024,
+        total_size: 20,
+    });
+
+    let part_limited: Vec<_> = part_limited.collect();
+    assert_eq!(part_limited.len(), 2);
+    assert_eq!(part_limited[1], Err(MultipartError::PartTooLarge));
+
+    let total_limited: Vec<_> = total_limited.collect();
+    assert_eq!(total_limited.len(), 2);
+    assert_eq!(total_limited[1], Err(MultipartError::TooLarge));
+}
+
+#[test]
+fn malformed_bodies() {
+    const NUM_TESTS: usize = 4;
+    let bodies: [&[u8]; NUM_TESTS] = [
+        b"no delimiters at all",
+        b"--boundary\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nunterminated",
+        b"--boundary\r\nContent-Disposition: form-data\r\nunterminated headers",
+        b"--boundaryjunk\r\n\r\ndata\r\n--boundary--",
+    ];
+
+    for body in bodies {
+        let parts: Vec<_> = Multipart::new(body, "boundary").collect();
+        assert_eq!(parts, vec![Err(MultipartError::Malformed)]);
+    }
+}
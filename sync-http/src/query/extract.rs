@@ -0,0 +1,41 @@
+use super::Query;
+use crate::errors::QueryError;
+use std::str::FromStr;
+
+/// Converts a query into a typed value, such as a struct of the expected
+/// parameters. With the `derive` feature this can be derived for structs.
+pub trait FromQuery: Sized {
+    fn from_query(query: &Query) -> Result<Self, QueryError>;
+}
+
+impl FromQuery for Query {
+    fn from_query(query: &Query) -> Result<Self, QueryError> {
+        Ok(query.clone())
+    }
+}
+
+/// Converts the first value of a key, which must be present.
+pub fn required<T: FromStr>(query: &Query, key: &str) -> Result<T, QueryError> {
+    optional(query, key)?.ok_or_else(|| QueryError::Missing(key.to_string()))
+}
+
+/// Converts the first value of a key, if it is present.
+pub fn optional<T: FromStr>(query: &Query, key: &str) -> Result<Option<T>, QueryError> {
+    query.get(key).map(|value| convert(key, value)).transpose()
+}
+
+/// Converts every value of a key.
+pub fn repeated<T: FromStr>(query: &Query, key: &str) -> Result<Vec<T>, QueryError> {
+    query
+        .get_all(key)
+        .into_iter()
+        .map(|value| convert(key, value))
+        .collect()
+}
+
+fn convert<T: FromStr>(key: &str, value: &str) -> Result<T, QueryError> {
+    value.parse().map_err(|_| QueryError::Invalid {
+        key: key.to_string(),
+        value: value.to_string(),
+    })
+}
@@ -0,0 +1,149 @@
+use crate::errors::QueryError;
+mod extract;
+pub use extract::*;
+#[cfg(feature = "derive")]
+pub use sync_http_derive::FromQuery;
+
+#[cfg(test)]
+mod tests;
+
+/// The key value pairs of an `application/x-www-form-urlencoded` string,
+/// such as the query of a uri. Keys may be repeated, and keep their order.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Query {
+    pub queries: Vec<KeyValue>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyValue {
+    pub key: String,
+    pub value: String,
+}
+
+impl Query {
+    pub fn parse(queries: String) -> Self {
+        let queries = queries.trim().trim_start_matches('?');
+        Self {
+            queries: queries
+                .split('&')
+                .map(str::to_string)
+                .filter_map(KeyValue::parse)
+                .collect(),
+        }
+    }
+
+    /// Encodes the query back into an `application/x-www-form-urlencoded`
+    /// string, without a leading `?`.
+    pub fn encode(&self) -> String {
+        self.queries
+            .iter()
+            .map(KeyValue::encode)
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+
+    pub fn push(&mut self, key: &str, value: &str) {
+        self.queries.push(KeyValue {
+            key: key.to_string(),
+            value: value.to_string(),
+        });
+    }
+
+    /// Gets the first value for the key.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.queries
+            .iter()
+            .find(|query| query.key == key)
+            .map(|query| query.value.as_str())
+    }
+
+    /// Gets every value for the key, in the order they were given.
+    pub fn get_all(&self, key: &str) -> Vec<&str> {
+        self.queries
+            .iter()
+            .filter(|query| query.key == key)
+            .map(|query| query.value.as_str())
+            .collect()
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.queries.iter().any(|query| query.key == key)
+    }
+
+    /// Converts the query into a typed value, see [`FromQuery`].
+    pub fn extract<T: FromQuery>(&self) -> Result<T, QueryError> {
+        T::from_query(self)
+    }
+}
+
+impl KeyValue {
+    pub fn parse(query: String) -> Option<Self> {
+        if query.is_empty() {
+            None?;
+        }
+
+        // Only the first `=` separates the key from the value
+        let (key, value) = query.split_once('=').unwrap_or((&query, ""));
+        Some(KeyValue {
+            key: decode_component(key),
+            value: decode_component(value),
+        })
+    }
+
+    pub fn encode(&self) -> String {
+        format!(
+            "{}={}",
+            encode_component(&self.key),
+            encode_component(&self.value)
+        )
+    }
+}
+
+/// Decodes `%XX` escapes into the bytes they represent, leaving any
+/// invalid escapes as they are.
+pub fn percent_decode(encoded: &str) -> Vec<u8> {
+    let bytes = encoded.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .filter(|_| bytes[i] == b'%')
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    decoded
+}
+
+/// Decodes a key or value of an `application/x-www-form-urlencoded`
+/// string, where `+` is a space.
+pub fn decode_component(encoded: &str) -> String {
+    let decoded = percent_decode(&encoded.replace('+', " "));
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Encodes a key or value of an `application/x-www-form-urlencoded`
+/// string, spaces become `+` and anything not alphanumeric or `*-._` is
+/// percent encoded.
+pub fn encode_component(component: &str) -> String {
+    let mut encoded = String::with_capacity(component.len());
+    for byte in component.bytes() {
+        match byte {
+            b' ' => encoded.push('+'),
+            b'*' | b'-' | b'.' | b'_' => encoded.push(byte as char),
+            _ if byte.is_ascii_alphanumeric() => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
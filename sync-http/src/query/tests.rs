@@ -0,0 +1,180 @@
+use super::*;
+use crate::errors::QueryError;
+
+#[test]
+fn parse_query() {
+    let query = Query::parse("?a=1&b=hello+world%21&empty=&flag".into());
+
+    assert_eq!(
+        query.queries,
+        vec![
+            KeyValue {
+                key: "a".into(),
+                value: "1".into()
+            },
+            KeyValue {
+                key: "b".into(),
+                value: "hello world!".into()
+            },
+            KeyValue {
+                key: "empty".into(),
+                value: "".into()
+            },
+            KeyValue {
+                key: "flag".into(),
+                value: "".into()
+            },
+        ]
+    );
+}
+
+#[test]
+fn parse_key_values() {
+    const NUM_TESTS: usize = 6;
+    let queries: [&str; NUM_TESTS] = [
+        "key=value",
+        "equation=1+1=2",
+        "name=%E2%9C%93",
+        "odd%20key=%zz%4",
+        "a+b=c%2Bd",
+        "",
+    ];
+    let test_vals: [Option<(&str, &str)>; NUM_TESTS] = [
+        Some(("key", "value")),
+        Some(("equation", "1 1=2")),
+        Some(("name", "\u{2713}")),
+        Some(("odd key", "%zz%4")),
+        Some(("a b", "c+d")),
+        None,
+    ];
+
+    for i in 0..NUM_TESTS {
+        let parsed = KeyValue::parse(queries[i].into());
+        let expected = test_vals[i].map(|(key, value)| KeyValue {
+            key: key.into(),
+            value: value.into(),
+        });
+        assert_eq!(parsed, expected);
+    }
+}
+
+#[test]
+fn query_lookup() {
+    let query = Query::parse("tag=rust&tag=http&page=2&tag=".into());
+
+    assert_eq!(query.get("page"), Some("2"));
+    assert_eq!(query.get("tag"), Some("rust"));
+    assert_eq!(query.get_all("tag"), vec!["rust", "http", ""]);
+    assert_eq!(query.get("missing"), None);
+    assert!(query.get_all("missing").is_empty());
+    assert!(query.contains("page"));
+    assert!(!query.contains("Page"));
+}
+
+#[test]
+fn encode_query() {
+    let mut query = Query::default();
+    query.push("name", "Jane Doe");
+    query.push("q", "a&b=c/d?\u{2713}");
+    query.push("safe", "*-._");
+
+    let encoded = query.encode();
+    assert_eq!(
+        encoded,
+        "name=Jane+Doe&q=a%26b%3Dc%2Fd%3F%E2%9C%93&safe=*-._"
+    );
+    assert_eq!(Query::parse(encoded), query);
+}
+
+#[derive(Debug, PartialEq)]
+struct Search {
+    q: String,
+    page: Option<u32>,
+    tags: Vec<String>,
+}
+
+impl FromQuery for Search {
+    fn from_query(query: &Query) -> Result<Self, QueryError> {
+        Ok(Self {
+            q: required(query, "q")?,
+            page: optional(query, "page")?,
+            tags: repeated(query, "tag")?,
+        })
+    }
+}
+
+#[test]
+fn extract_query() {
+    const NUM_TESTS: usize = 4;
+    let queries: [&str; NUM_TESTS] = [
+        "q=rust&page=2&tag=http&tag=sync",
+        "q=rust",
+        "page=2",
+        "q=rust&page=two",
+    ];
+    let test_vals: [Result<_, _>; NUM_TESTS] = [
+        Ok(Search {
+            q: "rust".into(),
+            page: Some(2),
+            tags: vec!["http".into(), "sync".into()],
+        }),
+        Ok(Search {
+            q: "rust".into(),
+            page: None,
+            tags: vec![],
+        }),
+        Err(QueryError::Missing("q".into())),
+        Err(QueryError::Invalid {
+            key: "page".into(),
+            value: "two".into(),
+        }),
+    ];
+
+    for i in 0..NUM_TESTS {
+        let query = Query::parse(queries[i].into());
+        assert_eq!(query.extract::<Search>(), test_vals[i]);
+    }
+}
+
+#[cfg(feature = "derive")]
+#[derive(Debug, PartialEq, FromQuery)]
+struct DerivedSearch {
+    q: String,
+    page: Option<u32>,
+    #[query(rename = "tag")]
+    tags: Vec<String>,
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn derive_query() {
+    const NUM_TESTS: usize = 4;
+    let queries: [&str; NUM_TESTS] = [
+        "q=rust&page=2&tag=http&tag=sync",
+        "q=rust&tags=http",
+        "page=2",
+        "q=rust&tag=http&page=two",
+    ];
+    let test_vals: [Result<_, _>; NUM_TESTS] = [
+        Ok(DerivedSearch {
+            q: "rust".into(),
+            page: Some(2),
+            tags: vec!["http".into(), "sync".into()],
+        }),
+        Ok(DerivedSearch {
+            q: "rust".into(),
+            page: None,
+            tags: vec![],
+        }),
+        Err(QueryError::Missing("q".into())),
+        Err(QueryError::Invalid {
+            key: "page".into(),
+            value: "two".into(),
+        }),
+    ];
+
+    for i in 0..NUM_TESTS {
+        let query = Query::parse(queries[i].into());
+        assert_eq!(query.extract::<DerivedSearch>(), test_vals[i]);
+    }
+}
@@ -1,11 +1,13 @@
 use super::ContentType;
 
-// TODO: Add more headers
+// TODO: Add more typed headers, any others are kept in the HeaderMap
 #[derive(Debug, PartialEq, Clone)]
 pub enum Header {
     Host(String),
     UserAgent(String),
     Accept(Vec<ContentType>),
+    ContentType(ContentType),
+    ContentLength(usize),
 }
 
 impl Header {
@@ -20,6 +22,8 @@ impl Header {
             "HOST" => Self::Host(head_value),
             "USER-AGENT" => Self::UserAgent(head_value),
             "ACCEPT" => Self::Accept(ContentType::parse_many(head_value)?),
+            "CONTENT-TYPE" => Self::ContentType(ContentType::parse(head_value)?),
+            "CONTENT-LENGTH" => Self::ContentLength(head_value.parse().ok()?),
             _ => None?,
         })
     }
@@ -0,0 +1,65 @@
+use super::Header;
+
+/// Every header sent with a request, looked up case insensitively. Headers
+/// may be repeated, and keep the order they were sent in.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct HeaderMap {
+    headers: Vec<(String, String)>,
+}
+
+impl HeaderMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a header, keeping any others with the same name.
+    pub fn append(&mut self, name: &str, value: &str) {
+        self.headers.push((name.to_string(), value.to_string()));
+    }
+
+    /// Gets the first value of the header.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value)
+    }
+
+    /// Gets every value of the header, in the order they were sent.
+    pub fn get_all(&self, name: &str) -> Vec<&str> {
+        self.iter()
+            .filter(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value)
+            .collect()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// Parses the first value of the header into its typed representation,
+    /// returning `None` if it is missing or has none.
+    pub fn typed(&self, name: &str) -> Option<Header> {
+        Header::parse(&format!("{name}:{}", self.get(name)?))
+    }
+
+    /// Parses every header which has a typed representation.
+    pub fn typed_all(&self) -> Vec<Header> {
+        self.iter()
+            .filter_map(|(name, value)| Header::parse(&format!("{name}:{value}")))
+            .collect()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.headers
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.headers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.headers.is_empty()
+    }
+}
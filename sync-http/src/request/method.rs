@@ -1,19 +1,69 @@
 use super::RequestError;
 
-// TODO: Implement more methods
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+/// The request methods defined by RFC 9110, with any other method
+/// token being kept as an extension method.
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Method {
     Get,
+    Head,
     Post,
+    Put,
+    Delete,
+    Connect,
+    Options,
+    Trace,
+    Patch,
+    Extension(String),
 }
 
+const STANDARD_METHODS: [&str; 9] = [
+    "GET", "HEAD", "POST", "PUT", "DELETE", "CONNECT", "OPTIONS", "TRACE", "PATCH",
+];
+
 impl Method {
     pub fn parse(method: &str) -> Result<Method, RequestError> {
         // Methods are case sensitive
         Ok(match method.trim() {
             "GET" => Method::Get,
+            "HEAD" => Method::Head,
             "POST" => Method::Post,
+            "PUT" => Method::Put,
+            "DELETE" => Method::Delete,
+            "CONNECT" => Method::Connect,
+            "OPTIONS" => Method::Options,
+            "TRACE" => Method::Trace,
+            "PATCH" => Method::Patch,
+            // A miscased standard method is more likely a mistake than an extension
+            method
+                if !method.is_empty()
+                    && method.bytes().all(is_token_char)
+                    && !STANDARD_METHODS
+                        .iter()
+                        .any(|standard| standard.eq_ignore_ascii_case(method)) =>
+            {
+                Method::Extension(method.to_string())
+            }
             _ => Err(RequestError::BadMethod)?,
         })
     }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Method::Get => "GET",
+            Method::Head => "HEAD",
+            Method::Post => "POST",
+            Method::Put => "PUT",
+            Method::Delete => "DELETE",
+            Method::Connect => "CONNECT",
+            Method::Options => "OPTIONS",
+            Method::Trace => "TRACE",
+            Method::Patch => "PATCH",
+            Method::Extension(method) => method,
+        }
+    }
+}
+
+/// The characters allowed in a token, as defined by RFC 9110.
+pub(crate) fn is_token_char(ch: u8) -> bool {
+    ch.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&ch)
 }
@@ -1,52 +1,69 @@
 use crate::errors::*;
 use crate::mime::*;
-use crate::query::Query;
+use crate::multipart::Multipart;
+use crate::query::{percent_decode, Query};
 pub mod header;
+pub mod header_map;
 pub mod method;
+pub mod params;
+pub mod reader;
 pub use header::*;
+pub use header_map::*;
 pub use method::*;
+pub use params::*;
+pub use reader::*;
 
 #[cfg(test)]
 mod tests;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Request {
     pub method: Method,
     pub uri: String,
     pub query: Query,
-    pub headers: Vec<Header>,
+    pub headers: HeaderMap,
+    pub body: Vec<u8>,
+    /// The named parameters of the route which matched the request.
+    pub params: Params,
 }
 
 impl Request {
-    pub fn parse(req: String) -> Result<Self, RequestError> {
-        // Remove everything after the headers from the request
-        let req: String = req.split("\r\n\r\n").take(1).collect();
-        // Split the request into its parts
-        let parts: Vec<&str> = req.split("\n").map(|line| line.trim()).collect();
-
-        // Parse out first line
-        let first = parts[0].split(" ").collect::<Vec<_>>();
+    pub fn parse(req: &[u8]) -> Result<Self, RequestError> {
+        // Separate the headers from the body of the request
+        let (req, body) = match req.windows(4).position(|w| w == b"\r\n\r\n") {
+            Some(end) => (&req[..end], req[end + 4..].to_vec()),
+            None => (req, vec![]),
+        };
+        // Split the request into its lines, allowing for bare line feeds
+        let mut lines = req
+            .split(|b| *b == b'\n')
+            .map(|line| line.strip_suffix(b"\r").unwrap_or(line));
+
+        // Parse out first line, which is only ever ascii
+        let first = lines.next().unwrap_or_default();
+        let first = std::str::from_utf8(first).map_err(|_| RequestError::BadUri)?;
+        let first = first.split(" ").collect::<Vec<_>>();
         if first.len() != 3 {
             return Err(RequestError::TooManyValues);
         }
         let method = Method::parse(first[0])?;
-        let uri_parts: Vec<&str> = first[1].split("?").collect();
-        let uri = sanatise_uri(uri_parts[0]);
-        let query = match uri_parts.get(1) {
-            Some(part) => Query::parse(part.to_string()),
-            None => Query::default(),
+        // Only the first `?` separates the path from the query
+        let (uri, query) = match first[1].split_once('?') {
+            Some((uri, query)) => (uri, Query::parse(query.to_string())),
+            None => (first[1], Query::default()),
         };
+        let uri = sanatise_uri(uri)?;
         // We only support HTTP/1.1
         if first[2].to_uppercase().as_str() != "HTTP/1.1" {
             return Err(RequestError::BadProtocol);
         }
 
         // Parse out headers
-        let headers = parts
-            .iter()
-            .skip(1)
-            .filter_map(|&line| Header::parse(line))
-            .collect::<Vec<_>>();
+        let mut headers = HeaderMap::new();
+        for line in lines.filter(|line| !line.is_empty()) {
+            let (name, value) = validate_header(line)?;
+            headers.append(&name, &value);
+        }
 
         // Return the request
         Ok(Request {
@@ -54,14 +71,124 @@ impl Request {
             uri,
             query,
             headers,
+            body,
+            params: Params::default(),
         })
     }
 }
 
-fn sanatise_uri(uri: &str) -> String {
-    uri.trim_matches('.')
-        .split("/")
-        .filter(|part| !part.is_empty())
-        .collect::<Vec<_>>()
-        .join("/")
+impl Request {
+    pub fn host(&self) -> Option<String> {
+        match self.headers.typed("Host")? {
+            Header::Host(host) => Some(host),
+            _ => None,
+        }
+    }
+
+    pub fn user_agent(&self) -> Option<String> {
+        match self.headers.typed("User-Agent")? {
+            Header::UserAgent(user_agent) => Some(user_agent),
+            _ => None,
+        }
+    }
+
+    pub fn accept(&self) -> Option<Vec<ContentType>> {
+        match self.headers.typed("Accept")? {
+            Header::Accept(content_types) => Some(content_types),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(&self) -> Option<ContentType> {
+        match self.headers.typed("Content-Type")? {
+            Header::ContentType(content_type) => Some(content_type),
+            _ => None,
+        }
+    }
+
+    pub fn content_length(&self) -> Option<usize> {
+        match self.headers.typed("Content-Length")? {
+            Header::ContentLength(length) => Some(length),
+            _ => None,
+        }
+    }
+
+    /// Checks whether the client will keep the connection open for more
+    /// requests, which it does unless it sends `Connection: close`.
+    pub fn keep_alive(&self) -> bool {
+        !self
+            .headers
+            .get_all("Connection")
+            .into_iter()
+            .flat_map(|options| options.split(','))
+            .any(|option| option.trim().eq_ignore_ascii_case("close"))
+    }
+
+    /// Decodes an `application/x-www-form-urlencoded` body, such as from
+    /// a submitted HTML form, returning `None` for any other content type.
+    pub fn form(&self) -> Option<Query> {
+        let content_type = self.content_type()?;
+        if content_type.0 != MediaType::Application || content_type.1 != MimeType::FormUrlEncoded {
+            return None;
+        }
+        Some(Query::parse(
+            String::from_utf8_lossy(&self.body).into_owned(),
+        ))
+    }
+
+    /// Reads a `multipart/form-data` body, such as from an HTML form which
+    /// uploads files, returning `None` for any other content type.
+    pub fn multipart(&self) -> Option<Multipart<'_>> {
+        let content_type = self.content_type()?;
+        if content_type.0 != MediaType::Multipart || content_type.1 != MimeType::FormData {
+            return None;
+        }
+        Some(Multipart::new(&self.body, content_type.boundary()?))
+    }
+}
+
+/// Removes empty and dot segments from the path, percent decoding each
+/// segment which must then be valid UTF-8. Segments are decoded before the
+/// dot segments are removed, so that an encoded `..` can't climb out of a
+/// route, and an encoded `/` is rejected rather than splitting a segment.
+fn sanatise_uri(uri: &str) -> Result<String, RequestError> {
+    let mut segments = Vec::new();
+    for part in uri.split("/").filter(|part| !part.is_empty()) {
+        let part = String::from_utf8(percent_decode(part)).map_err(|_| RequestError::BadUri)?;
+        match part.as_str() {
+            "." => {}
+            // Going up from the root stays at the root
+            ".." => {
+                segments.pop();
+            }
+            _ if part.contains('/') => return Err(RequestError::BadUri),
+            _ => segments.push(part),
+        }
+    }
+    Ok(segments.join("/"))
+}
+
+/// Validates a header line as defined by RFC 9110, the name must be a
+/// token and the value must not contain control characters. Values are
+/// decoded as UTF-8, falling back to Latin-1 for any other bytes.
+fn validate_header(line: &[u8]) -> Result<(String, String), RequestError> {
+    let colon = line
+        .iter()
+        .position(|b| *b == b':')
+        .ok_or(RequestError::BadHeader)?;
+    let (name, value) = (&line[..colon], &line[colon + 1..]);
+    // This also rejects whitespace before the colon and folded lines
+    if name.is_empty() || !name.iter().all(|b| is_token_char(*b)) {
+        return Err(RequestError::BadHeader);
+    }
+    if value.iter().any(|b| b.is_ascii_control() && *b != b'\t') {
+        return Err(RequestError::BadHeader);
+    }
+    let value = match std::str::from_utf8(value) {
+        Ok(value) => value.to_string(),
+        Err(_) => value.iter().map(|b| *b as char).collect(),
+    };
+    // Each byte of the name is ascii
+    let name: String = name.iter().map(|b| *b as char).collect();
+    Ok((name, value.trim_matches([' ', '\t']).to_string()))
 }
@@ -0,0 +1,35 @@
+use regex::{Captures, Regex};
+use std::{collections::HashMap, str::FromStr};
+
+/// The values captured by the named parameters of a route.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Params(HashMap<String, String>);
+
+impl Params {
+    pub fn from_captures(route: &Regex, captures: &Captures) -> Self {
+        Self(
+            route
+                .capture_names()
+                .flatten()
+                .map(|name| {
+                    let value = captures.name(name).map_or("", |value| value.as_str());
+                    (name.to_string(), value.to_string())
+                })
+                .collect(),
+        )
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0.get(name).map(String::as_str)
+    }
+
+    /// Gets a parameter converted to the given type, returning `None` if
+    /// it is missing or fails to convert.
+    pub fn get_as<T: FromStr>(&self, name: &str) -> Option<T> {
+        self.get(name)?.parse().ok()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.0.contains_key(name)
+    }
+}
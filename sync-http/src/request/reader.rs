@@ -0,0 +1,283 @@
+use super::{HeaderMap, Request, RequestError};
+use crate::ServerResult;
+use std::io::Read;
+
+/// The limits on the size of a request, by default:
+/// - request_line: 8 KiB
+/// - header_bytes: 64 KiB, for every header together
+/// - header_count: 100
+/// - body_size: 16 MiB
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestLimits {
+    pub request_line: usize,
+    pub header_bytes: usize,
+    pub header_count: usize,
+    pub body_size: usize,
+}
+
+impl Default for RequestLimits {
+    fn default() -> Self {
+        Self {
+            request_line: 8 * 1024,
+            header_bytes: 64 * 1024,
+            header_count: 100,
+            body_size: 16 * 1024 * 1024,
+        }
+    }
+}
+
+/// Reads requests off of a stream, decoding the framing of the body.
+pub struct RequestReader<R> {
+    stream: R,
+    buf: Vec<u8>,
+    limits: RequestLimits,
+}
+
+impl<R: Read> RequestReader<R> {
+    pub fn new(stream: R) -> Self {
+        Self {
+            stream,
+            buf: vec![],
+            limits: RequestLimits::default(),
+        }
+    }
+
+    pub fn limits(mut self, limits: RequestLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Gets the stream being read from.
+    pub fn get_ref(&self) -> &R {
+        &self.stream
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.stream
+    }
+
+    /// Checks whether any of the next request has already been read, not
+    /// counting empty lines before it.
+    pub fn has_buffered(&self) -> bool {
+        self.buf.iter().any(|b| !matches!(b, b'\r' | b'\n'))
+    }
+
+    /// Reads the next request, any bytes read past its end are kept as the
+    /// start of the request after it, such as when requests are pipelined.
+    pub fn read_request(&mut self) -> ServerResult<Request> {
+        let mut request = self.read_head()?;
+        self.read_body(&mut request)?;
+        Ok(request)
+    }
+
+    /// Reads the request line and headers of the next request, leaving its
+    /// body to be read by `read_body`.
+    pub fn read_head(&mut self) -> ServerResult<Request> {
+        let limits = self.limits;
+        // Empty lines before the request line are ignored, as RFC 9112
+        // recommends, such as a stray line ending sent after a body
+        loop {
+            let blank = self
+                .buf
+                .iter()
+                .take_while(|b| matches!(b, b'\r' | b'\n'))
+                .count();
+            self.buf.drain(..blank);
+            if !self.buf.is_empty() {
+                break;
+            }
+            self.fill()?;
+        }
+        // Nothing is buffered past the limits while looking for their ends
+        let line_end = self.find(b"\n", limits.request_line, RequestError::RequestLineTooLong)?;
+        let head_end =
+            self.find_head_end(line_end + 1, line_end.saturating_add(limits.header_bytes))?;
+        let head: Vec<u8> = self.buf.drain(..=head_end).collect();
+        let request = Request::parse(&head)?;
+        if request.headers.len() > limits.header_count {
+            Err(RequestError::TooManyHeaders)?;
+        }
+        Ok(request)
+    }
+
+    pub fn read_body(&mut self, request: &mut Request) -> ServerResult<()> {
+        request.body = if is_chunked(&request.headers)? {
+            self.read_chunked()?
+        } else {
+            let length = content_length(&request.headers)?;
+            if length > self.limits.body_size {
+                Err(RequestError::BodyTooLarge)?;
+            }
+            self.take(length)?
+        };
+        Ok(())
+    }
+
+    fn read_chunked(&mut self) -> ServerResult<Vec<u8>> {
+        let max_line = self.limits.header_bytes;
+        let mut body = vec![];
+        loop {
+            let line = self.take_until(b"\r\n", max_line, RequestError::BadChunk)?;
+            // Chunk extensions carry no meaning for us, so are skipped
+            let size = line.split(|b| *b == b';').next().unwrap_or_default();
+            let size = chunk_size(size)?;
+            if size == 0 {
+                break;
+            }
+            // The body read so far is within the limit, so this can't underflow
+            if size > self.limits.body_size - body.len() {
+                Err(RequestError::BodyTooLarge)?;
+            }
+            body.extend(self.take(size)?);
+            if !self
+                .take_until(b"\r\n", 0, RequestError::BadChunk)?
+                .is_empty()
+            {
+                Err(RequestError::BadChunk)?;
+            }
+        }
+        // Trailers end with an empty line, and are discarded
+        let mut trailers = 0;
+        loop {
+            let max_trailer = max_line.saturating_sub(trailers);
+            let trailer = self.take_until(b"\r\n", max_trailer, RequestError::HeadersTooLarge)?;
+            if trailer.is_empty() {
+                break;
+            }
+            trailers += trailer.len() + 2;
+        }
+        Ok(body)
+    }
+
+    /// Reads more of the stream into the buffer.
+    fn fill(&mut self) -> ServerResult<()> {
+        let mut buf = [0; 128];
+        let length = self.stream.read(&mut buf)?;
+        if length == 0 {
+            Err(RequestError::Incomplete)?;
+        }
+        self.buf.extend_from_slice(&buf[..length]);
+        Ok(())
+    }
+
+    /// Takes everything up to the delimiter, removing the delimiter too.
+    fn take_until(
+        &mut self,
+        delim: &[u8],
+        limit: usize,
+        error: RequestError,
+    ) -> ServerResult<Vec<u8>> {
+        let end = self.find(delim, limit, error)?;
+        let taken = self.buf[..end].to_vec();
+        self.buf.drain(..end + delim.len());
+        Ok(taken)
+    }
+
+    /// Reads until the delimiter is buffered, returning where it starts.
+    /// If the delimiter doesn't start within the limit then the error is
+    /// returned, without reading any further.
+    fn find(&mut self, delim: &[u8], limit: usize, error: RequestError) -> ServerResult<usize> {
+        let mut searched = 0;
+        loop {
+            if let Some(end) = self.buf[searched..]
+                .windows(delim.len())
+                .position(|w| w == delim)
+            {
+                let end = searched + end;
+                if end > limit {
+                    return Err(error.into());
+                }
+                return Ok(end);
+            }
+            if self.buf.len() >= limit.saturating_add(delim.len()) {
+                return Err(error.into());
+            }
+            // The delimiter may have been split across reads
+            searched = self.buf.len().saturating_sub(delim.len() - 1);
+            self.fill()?;
+        }
+    }
+
+    /// Finds the line feed which ends the empty line after the headers,
+    /// allowing for bare line feeds as `Request::parse` does.
+    fn find_head_end(&mut self, start: usize, limit: usize) -> ServerResult<usize> {
+        let mut searched = start;
+        loop {
+            let blank = (searched..self.buf.len()).find(|end| {
+                let line = &self.buf[..*end];
+                self.buf[*end] == b'\n' && (line.ends_with(b"\n") || line.ends_with(b"\n\r"))
+            });
+            if let Some(end) = blank {
+                if end > limit {
+                    return Err(RequestError::HeadersTooLarge.into());
+                }
+                return Ok(end);
+            }
+            if self.buf.len() > limit {
+                return Err(RequestError::HeadersTooLarge.into());
+            }
+            searched = self.buf.len().max(start);
+            self.fill()?;
+        }
+    }
+
+    fn take(&mut self, length: usize) -> ServerResult<Vec<u8>> {
+        while self.buf.len() < length {
+            self.fill()?;
+        }
+        Ok(self.buf.drain(..length).collect())
+    }
+}
+
+fn chunk_size(size: &[u8]) -> Result<usize, RequestError> {
+    let size = std::str::from_utf8(size).map_err(|_| RequestError::BadChunk)?;
+    let size = size.trim_matches([' ', '\t']);
+    if size.is_empty() || !size.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(RequestError::BadChunk);
+    }
+    usize::from_str_radix(size, 16).map_err(|_| RequestError::BadChunk)
+}
+
+/// Finds the length of the body from the `Content-Length` header, if
+/// there is no such header then the request has no body.
+pub fn content_length(headers: &HeaderMap) -> Result<usize, RequestError> {
+    let mut lengths = headers
+        .get_all("Content-Length")
+        .into_iter()
+        .flat_map(|lengths| lengths.split(','))
+        .map(|length| {
+            length
+                .trim()
+                .parse()
+                .map_err(|_| RequestError::BadContentLength)
+        });
+    let Some(length) = lengths.next().transpose()? else {
+        return Ok(0);
+    };
+    // Repeated lengths are only allowed if they all agree
+    for other in lengths {
+        if other? != length {
+            return Err(RequestError::BadContentLength);
+        }
+    }
+    Ok(length)
+}
+
+/// Checks whether the body is framed with chunked `Transfer-Encoding`,
+/// which must be the only encoding applied when present. Other encodings
+/// aren't decoded, so are rejected rather than passing on an encoded body.
+pub fn is_chunked(headers: &HeaderMap) -> Result<bool, RequestError> {
+    let encodings = headers.get_all("Transfer-Encoding");
+    if encodings.is_empty() {
+        return Ok(false);
+    }
+    let mut encodings = encodings
+        .iter()
+        .flat_map(|encodings| encodings.split(','))
+        .map(str::trim)
+        .filter(|encoding| !encoding.is_empty());
+    match (encodings.next(), encodings.next()) {
+        (Some(encoding), None) if encoding.eq_ignore_ascii_case("chunked") => Ok(true),
+        _ => Err(RequestError::UnsupportedTransferEncoding),
+    }
+}
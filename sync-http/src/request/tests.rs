@@ -3,18 +3,29 @@ use super::*;
 #[test]
 fn example_request() {
     let request = Request::parse(
-        "GET / HTTP/1.1\r\n\
+        b"GET / HTTP/1.1\r\n\
             Host: localhost:8080\r\n\
             User-Agent: curl/8.5.0\r\n\
-            Accept: */*\r\n\r\n"
-            .into(),
+            Accept: */*\r\n\r\n",
     );
 
+    let mut headers = HeaderMap::new();
+    headers.append("Host", "localhost:8080");
+    headers.append("User-Agent", "curl/8.5.0");
+    headers.append("Accept", "*/*");
     let accurate = Request {
         method: Method::Get,
         uri: "".into(),
         query: Query::default(),
-        headers: vec![
+        headers,
+        body: vec![],
+        params: Params::default(),
+    };
+
+    assert_eq!(request, Ok(accurate.clone()));
+    assert_eq!(
+        accurate.headers.typed_all(),
+        vec![
             Header::Host("localhost:8080".into()),
             Header::UserAgent("curl/8.5.0".into()),
             Header::Accept(vec![ContentType(
@@ -22,20 +33,369 @@ fn example_request() {
                 MimeType::All,
                 MimeSuffix::None,
                 1.0,
+                vec![],
             )]),
-        ],
-    };
+        ]
+    );
+}
+
+#[test]
+fn header_map() {
+    let request = Request::parse(
+        b"GET / HTTP/1.1\r\n\
+            Host: localhost:8080\r\n\
+            Accept: text/html\r\n\
+            Cookie: a=1\r\n\
+            X-Custom-Header:  custom value \r\n\
+            cookie: b=2\r\n\r\n",
+    )
+    .unwrap();
+
+    assert_eq!(request.headers.len(), 5);
+    assert_eq!(request.headers.get("x-custom-header"), Some("custom value"));
+    assert_eq!(request.headers.get("COOKIE"), Some("a=1"));
+    assert_eq!(request.headers.get_all("Cookie"), vec!["a=1", "b=2"]);
+    assert!(!request.headers.contains("Authorization"));
+    assert_eq!(request.host().as_deref(), Some("localhost:8080"));
+    assert_eq!(request.user_agent(), None);
+    assert_eq!(
+        request.accept(),
+        Some(vec![ContentType(
+            MediaType::Text,
+            MimeType::HTML,
+            MimeSuffix::None,
+            1.0,
+            vec![],
+        )])
+    );
+    assert_eq!(
+        request.headers.typed_all(),
+        vec![
+            Header::Host("localhost:8080".into()),
+            Header::Accept(vec![ContentType(
+                MediaType::Text,
+                MimeType::HTML,
+                MimeSuffix::None,
+                1.0,
+                vec![],
+            )]),
+        ]
+    );
+}
 
-    assert_eq!(request, Ok(accurate));
+#[test]
+fn request_with_body() {
+    let request = Request::parse(
+        b"POST /submit HTTP/1.1\r\n\
+            Host: localhost:8080\r\n\r\n\
+            name=value",
+    )
+    .unwrap();
+
+    assert_eq!(request.method, Method::Post);
+    assert_eq!(request.uri, "submit");
+    assert_eq!(request.body, b"name=value");
 }
 
 #[test]
-fn header_parsing() {
+fn request_with_query() {
+    let request =
+        Request::parse(b"GET /search?q=what?&page=2 HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+
+    assert_eq!(request.uri, "search");
+    assert_eq!(request.query.get("q"), Some("what?"));
+    assert_eq!(request.query.get("page"), Some("2"));
+}
+
+#[test]
+fn form_body() {
+    let form = Request::parse(
+        b"POST /close HTTP/1.1\r\n\
+            Content-Type: application/x-www-form-urlencoded; charset=UTF-8\r\n\r\n\
+            name=Jane+Doe&confirm=yes",
+    )
+    .unwrap();
+    let not_form = Request::parse(
+        b"POST /close HTTP/1.1\r\n\
+            Content-Type: application/json\r\n\r\n\
+            {}",
+    )
+    .unwrap();
+
+    let form = form.form().unwrap();
+    assert_eq!(form.get("name"), Some("Jane Doe"));
+    assert_eq!(form.get("confirm"), Some("yes"));
+    assert_eq!(not_form.form(), None);
+}
+
+#[test]
+fn keep_alive() {
+    const NUM_TESTS: usize = 4;
+    let heads: [&str; NUM_TESTS] = [
+        "GET / HTTP/1.1\r\n\r\n",
+        "GET / HTTP/1.1\r\nConnection: keep-alive\r\n\r\n",
+        "GET / HTTP/1.1\r\nconnection: Upgrade, CLOSE\r\n\r\n",
+        "GET / HTTP/1.1\r\nConnection: upgrade\r\nConnection: close\r\n\r\n",
+    ];
+    let test_vals: [bool; NUM_TESTS] = [true, true, false, false];
+
+    for i in 0..NUM_TESTS {
+        let request = Request::parse(heads[i].as_bytes()).unwrap();
+        assert_eq!(request.keep_alive(), test_vals[i]);
+    }
+}
+
+#[test]
+fn multipart_body() {
+    let request = Request::parse(
+        b"POST /upload HTTP/1.1\r\n\
+            Content-Type: multipart/form-data; boundary=\"a;b\"\r\n\
+            Content-Length: 66\r\n\r\n\
+            --a;b\r\n\
+            Content-Disposition: form-data; name=\"x\"\r\n\r\n\
+            1\r\n\
+            --a;b--",
+    )
+    .unwrap();
+
+    assert_eq!(request.content_length(), Some(66));
+    let parts = request
+        .multipart()
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(parts.len(), 1);
+    assert_eq!(parts[0].name.as_deref(), Some("x"));
+    assert_eq!(parts[0].text(), "1");
+
+    let no_boundary =
+        Request::parse(b"POST / HTTP/1.1\r\nContent-Type: multipart/form-data\r\n\r\n").unwrap();
+    assert!(no_boundary.multipart().is_none());
+}
+
+#[test]
+fn non_ascii_request() {
+    let request = Request::parse(
+        "GET /caf%C3%A9/na\u{ef}ve HTTP/1.1\r\n\
+            User-Agent: caf\u{e9}\r\n\
+            Referer: http://localhost/\u{1f600}\r\n\r\n"
+            .as_bytes(),
+    )
+    .unwrap();
+
+    assert_eq!(request.uri, "caf\u{e9}/na\u{ef}ve");
+    assert_eq!(request.user_agent().as_deref(), Some("caf\u{e9}"));
+
+    // Latin-1 header values are kept as they are
+    let request = Request::parse(b"GET / HTTP/1.1\r\nUser-Agent: caf\xe9\r\n\r\n").unwrap();
+    assert_eq!(request.user_agent().as_deref(), Some("caf\u{e9}"));
+}
+
+#[test]
+fn dot_segments() {
     const NUM_TESTS: usize = 5;
+    let uris: [&str; NUM_TESTS] = [
+        "/static/./css/../logo.png",
+        "/static/../../etc/passwd",
+        "/static/%2e%2e/%2e%2e/etc/passwd",
+        "/static/%2E/logo.png",
+        "/static/..logo.png",
+    ];
+    let test_vals: [&str; NUM_TESTS] = [
+        "static/logo.png",
+        "etc/passwd",
+        "etc/passwd",
+        "static/logo.png",
+        "static/..logo.png",
+    ];
+
+    for i in 0..NUM_TESTS {
+        let request = Request::parse(format!("GET {} HTTP/1.1\r\n\r\n", uris[i]).as_bytes());
+        assert_eq!(request.unwrap().uri, test_vals[i]);
+    }
+}
+
+#[test]
+fn invalid_requests() {
+    const NUM_TESTS: usize = 6;
+    let requests: [&[u8]; NUM_TESTS] = [
+        b"GET /%FF HTTP/1.1\r\n\r\n",
+        b"GET /static/a%2Fb HTTP/1.1\r\n\r\n",
+        b"GET / HTTP/1.1\r\nHost : localhost\r\n\r\n",
+        b"GET / HTTP/1.1\r\nHost: local\x00host\r\n\r\n",
+        b"GET / HTTP/1.1\r\nUser-Agent: curl\r\n  /8.5.0\r\n\r\n",
+        b"GET / HTTP/1.1\r\nNo colon\r\n\r\n",
+    ];
+    let test_vals: [RequestError; NUM_TESTS] = [
+        RequestError::BadUri,
+        RequestError::BadUri,
+        RequestError::BadHeader,
+        RequestError::BadHeader,
+        RequestError::BadHeader,
+        RequestError::BadHeader,
+    ];
+
+    for i in 0..NUM_TESTS {
+        assert_eq!(Request::parse(requests[i]), Err(test_vals[i].clone()));
+    }
+}
+
+#[test]
+fn body_length() {
+    const NUM_TESTS: usize = 6;
+    let heads: [&str; NUM_TESTS] = [
+        "POST / HTTP/1.1\r\nContent-Length: 12\r\n\r\n",
+        "POST / HTTP/1.1\r\ncontent-LENGTH:3\r\n\r\n",
+        "GET / HTTP/1.1\r\nHost: localhost:8080\r\n\r\n",
+        "POST / HTTP/1.1\r\nContent-Length: -1\r\n\r\n",
+        "POST / HTTP/1.1\r\nContent-Length: 5, 5\r\nContent-Length: 5\r\n\r\n",
+        "POST / HTTP/1.1\r\nContent-Length: 5\r\nContent-Length: 6\r\n\r\n",
+    ];
+    let test_vals: [Result<_, _>; NUM_TESTS] = [
+        Ok(12),
+        Ok(3),
+        Ok(0),
+        Err(RequestError::BadContentLength),
+        Ok(5),
+        Err(RequestError::BadContentLength),
+    ];
+
+    for i in 0..NUM_TESTS {
+        let request = Request::parse(heads[i].as_bytes()).unwrap();
+        assert_eq!(content_length(&request.headers), test_vals[i]);
+    }
+}
+
+#[test]
+fn content_length_body() {
+    let mut reader = RequestReader::new(
+        "POST / HTTP/1.1\r\n\
+            Content-Length: 5\r\n\r\n\
+            helloGET"
+            .as_bytes(),
+    );
+    let request = reader.read_request().unwrap();
+
+    assert_eq!(request.body, b"hello");
+}
+
+#[test]
+fn chunked_body() {
+    let mut reader = RequestReader::new(
+        "POST / HTTP/1.1\r\n\
+            Transfer-Encoding: chunked\r\n\r\n\
+            5\r\nhello\r\n\
+            7;name=value\r\n, world\r\n\
+            0\r\n\
+            Expires: never\r\n\r\n"
+            .as_bytes(),
+    );
+    let request = reader.read_request().unwrap();
+
+    assert_eq!(request.body, b"hello, world");
+}
+
+#[test]
+fn bare_line_feeds() {
+    let mut reader = RequestReader::new(
+        "GET /first HTTP/1.1\nHost: a\n\n\
+            GET /second HTTP/1.1\r\nHost: b\r\n\n\
+            POST /third HTTP/1.1\nContent-Length: 4\n\nbody"
+            .as_bytes(),
+    );
+
+    const NUM_TESTS: usize = 3;
+    let test_vals: [(&str, Option<&str>, &[u8]); NUM_TESTS] = [
+        ("first", Some("a"), b""),
+        ("second", Some("b"), b""),
+        ("third", None, b"body"),
+    ];
+
+    for (uri, host, body) in test_vals {
+        let request = reader.read_request().unwrap();
+        assert_eq!(request.uri, uri);
+        assert_eq!(request.headers.get("Host"), host);
+        assert_eq!(request.body, body);
+    }
+}
+
+#[test]
+fn leading_empty_lines() {
+    let mut reader = RequestReader::new(
+        "\r\nGET /first HTTP/1.1\r\n\r\n\
+            POST /second HTTP/1.1\r\nContent-Length: 4\r\n\r\nbody\r\n\
+            \n\r\nGET /third HTTP/1.1\r\n\r\n\
+            \r\n"
+            .as_bytes(),
+    );
+
+    for uri in ["first", "second", "third"] {
+        assert_eq!(reader.read_request().unwrap().uri, uri);
+    }
+    // A stray line ending isn't the start of another request
+    assert!(!reader.has_buffered());
+}
+
+#[test]
+fn malformed_chunks() {
+    const NUM_TESTS: usize = 4;
+    let bodies: [&str; NUM_TESTS] = [
+        "x\r\nhello\r\n0\r\n\r\n",
+        "\r\nhello\r\n0\r\n\r\n",
+        "3\r\nhello\r\n0\r\n\r\n",
+        "fffffffffffffffffffff\r\nhello\r\n0\r\n\r\n",
+    ];
+
+    for body in bodies {
+        let request = format!("POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n{body}");
+        let error = RequestReader::new(request.as_bytes())
+            .read_request()
+            .unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<RequestError>(),
+            Some(&RequestError::BadChunk)
+        );
+    }
+}
+
+#[test]
+fn transfer_encoding() {
+    const NUM_TESTS: usize = 7;
+    let heads: [&str; NUM_TESTS] = [
+        "POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n",
+        "POST / HTTP/1.1\r\ntransfer-encoding: Chunked\r\n\r\n",
+        "POST / HTTP/1.1\r\nContent-Length: 3\r\n\r\n",
+        "POST / HTTP/1.1\r\nTransfer-Encoding: chunked, gzip\r\n\r\n",
+        "POST / HTTP/1.1\r\nTransfer-Encoding: gzip, chunked\r\n\r\n",
+        "POST / HTTP/1.1\r\nTransfer-Encoding: unknown\r\nTransfer-Encoding: chunked\r\n\r\n",
+        "POST / HTTP/1.1\r\nTransfer-Encoding: chunked, chunked\r\n\r\n",
+    ];
+    let test_vals: [Result<_, _>; NUM_TESTS] = [
+        Ok(true),
+        Ok(true),
+        Ok(false),
+        Err(RequestError::UnsupportedTransferEncoding),
+        Err(RequestError::UnsupportedTransferEncoding),
+        Err(RequestError::UnsupportedTransferEncoding),
+        Err(RequestError::UnsupportedTransferEncoding),
+    ];
+
+    for i in 0..NUM_TESTS {
+        let request = Request::parse(heads[i].as_bytes()).unwrap();
+        assert_eq!(is_chunked(&request.headers), test_vals[i]);
+    }
+}
+
+#[test]
+fn header_parsing() {
+    const NUM_TESTS: usize = 8;
     let headers: [&str; NUM_TESTS] = [
         "Host: localhost:8080",
         "user-AGENT: curl/8.5.0",
         "AcCePt: text/html",
+        "Content-Type: application/x-www-form-urlencoded",
+        "content-length: 42",
+        "Content-Length: -1",
         "Pragma: no-cache",
         "",
     ];
@@ -47,14 +407,28 @@ fn header_parsing() {
             MimeType::HTML,
             MimeSuffix::None,
             1.0,
+            vec![],
         )])),
+        Some(Header::ContentType(ContentType(
+            MediaType::Application,
+            MimeType::FormUrlEncoded,
+            MimeSuffix::None,
+            1.0,
+            vec![],
+        ))),
+        Some(Header::ContentLength(42)),
+        None,
         None,
         None,
     ];
 
     // Will fail to compile if more headers are added
     match test_vals[0].clone().unwrap() {
-        Header::Host(_) | Header::UserAgent(_) | Header::Accept(_) => {}
+        Header::Host(_)
+        | Header::UserAgent(_)
+        | Header::Accept(_)
+        | Header::ContentType(_)
+        | Header::ContentLength(_) => {}
     }
 
     for i in 0..NUM_TESTS {
@@ -65,22 +439,111 @@ fn header_parsing() {
 
 #[test]
 fn method_parsing() {
-    const NUM_TESTS: usize = 4;
-    let methods: [_; NUM_TESTS] = ["GET", "POST", "get", "post"];
+    const NUM_TESTS: usize = 14;
+    let methods: [_; NUM_TESTS] = [
+        "GET",
+        "HEAD",
+        "POST",
+        "PUT",
+        "DELETE",
+        "CONNECT",
+        "OPTIONS",
+        "TRACE",
+        "PATCH",
+        "PROPFIND",
+        "get",
+        "post",
+        "BAD/METHOD",
+        "",
+    ];
     let test_vals: [Result<_, _>; NUM_TESTS] = [
         Ok(Method::Get),
+        Ok(Method::Head),
         Ok(Method::Post),
+        Ok(Method::Put),
+        Ok(Method::Delete),
+        Ok(Method::Connect),
+        Ok(Method::Options),
+        Ok(Method::Trace),
+        Ok(Method::Patch),
+        Ok(Method::Extension("PROPFIND".into())),
+        Err(RequestError::BadMethod),
+        Err(RequestError::BadMethod),
         Err(RequestError::BadMethod),
         Err(RequestError::BadMethod),
     ];
 
     // Will fail to compile if more methods are added
     match Method::Get {
-        Method::Get | Method::Post => {}
+        Method::Get
+        | Method::Head
+        | Method::Post
+        | Method::Put
+        | Method::Delete
+        | Method::Connect
+        | Method::Options
+        | Method::Trace
+        | Method::Patch
+        | Method::Extension(_) => {}
     }
 
     for i in 0..NUM_TESTS {
         let parsed = Method::parse(methods[i]);
         assert_eq!(parsed, test_vals[i]);
+        if let Ok(method) = parsed {
+            assert_eq!(method.as_str(), methods[i]);
+        }
     }
 }
+
+#[test]
+fn reader_limits() {
+    let limits = RequestLimits {
+        request_line: 16,
+        header_bytes: 32,
+        header_count: 1,
+        body_size: 4,
+    };
+    const NUM_TESTS: usize = 6;
+    let requests: [&str; NUM_TESTS] = [
+        "GET /a-long-path HTTP/1.1\r\n\r\n",
+        "GET / HTTP/1.1\r\nUser-Agent: a-very-long-user-agent\r\n\r\n",
+        "GET / HTTP/1.1\r\nA: 1\r\nB: 2\r\n\r\n",
+        "POST / HTTP/1.1\r\nContent-Length: 5\r\n\r\nhello",
+        "POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n3\r\nabc\r\n2\r\nde\r\n0\r\n\r\n",
+        "POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n1\r\na\r\nFFFFFFFFFFFFFFFF\r\n",
+    ];
+    let test_vals: [RequestError; NUM_TESTS] = [
+        RequestError::RequestLineTooLong,
+        RequestError::HeadersTooLarge,
+        RequestError::TooManyHeaders,
+        RequestError::BodyTooLarge,
+        RequestError::BodyTooLarge,
+        RequestError::BodyTooLarge,
+    ];
+
+    for i in 0..NUM_TESTS {
+        let error = RequestReader::new(requests[i].as_bytes())
+            .limits(limits)
+            .read_request()
+            .unwrap_err();
+        assert_eq!(error.downcast_ref::<RequestError>(), Some(&test_vals[i]));
+    }
+}
+
+#[test]
+fn unbounded_reader_limits() {
+    let limits = RequestLimits {
+        request_line: usize::MAX,
+        header_bytes: usize::MAX,
+        header_count: usize::MAX,
+        body_size: usize::MAX,
+    };
+    let request = RequestReader::new(
+        "POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n3\r\nabc\r\n0\r\n\r\n".as_bytes(),
+    )
+    .limits(limits)
+    .read_request()
+    .unwrap();
+    assert_eq!(request.body, b"abc");
+}
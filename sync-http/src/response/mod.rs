@@ -0,0 +1,181 @@
+use crate::{
+    errors::{MultipartError, QueryError, RequestError},
+    request::is_token_char,
+};
+mod status;
+pub use status::*;
+
+#[cfg(test)]
+mod tests;
+
+/// The largest chunk written for a chunked body.
+const CHUNK_SIZE: usize = 16 * 1024;
+
+/// A response to be written back to the client, by default this is an
+/// empty `200 OK`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Response {
+    pub status: StatusCode,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Default for Response {
+    fn default() -> Self {
+        Self::new(StatusCode::Ok)
+    }
+}
+
+impl Response {
+    pub fn new(status: StatusCode) -> Self {
+        Self {
+            status,
+            headers: vec![],
+            body: vec![],
+        }
+    }
+
+    /// Creates a `200 OK` response with the given body.
+    pub fn ok(body: impl Into<Vec<u8>>) -> Self {
+        Self::new(StatusCode::Ok).body(body)
+    }
+
+    /// Creates a `303 See Other` response, which redirects the client to
+    /// the location using a GET request.
+    pub fn redirect(location: &str) -> Self {
+        Self::new(StatusCode::SeeOther).header("Location", location)
+    }
+
+    /// Adds a header to the response, headers are allowed to repeat. Names
+    /// which aren't tokens are left out when the response is written, and
+    /// control characters are removed from values, so that neither can
+    /// add headers of their own.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// Checks for a header, headers are case insensitive.
+    pub fn has_header(&self, name: &str) -> bool {
+        self.headers
+            .iter()
+            .any(|(header, _)| header.eq_ignore_ascii_case(name))
+    }
+
+    /// Checks for a header with the token in its comma separated values,
+    /// such as `Connection: close`, tokens are case insensitive.
+    pub fn has_header_token(&self, name: &str, token: &str) -> bool {
+        self.headers
+            .iter()
+            .filter(|(header, _)| header.eq_ignore_ascii_case(name))
+            .flat_map(|(_, value)| value.split(','))
+            .any(|value| value.trim().eq_ignore_ascii_case(token))
+    }
+
+    /// Sends the body with chunked `Transfer-Encoding` rather than with a
+    /// `Content-Length`.
+    pub fn chunked(self) -> Self {
+        self.header("Transfer-Encoding", "chunked")
+    }
+
+    pub fn is_chunked(&self) -> bool {
+        self.has_header_token("Transfer-Encoding", "chunked")
+    }
+
+    /// Serialises the response into the bytes to be sent to the client,
+    /// framing the body with a `Content-Length` header if it is not
+    /// chunked and one has not been set.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.head_bytes();
+        if !self.is_chunked() {
+            bytes.extend_from_slice(&self.body);
+            return bytes;
+        }
+        for chunk in self.body.chunks(CHUNK_SIZE) {
+            bytes.extend(format!("{:x}\r\n", chunk.len()).into_bytes());
+            bytes.extend_from_slice(chunk);
+            bytes.extend_from_slice(b"\r\n");
+        }
+        bytes.extend_from_slice(b"0\r\n\r\n");
+        bytes
+    }
+
+    /// Serialises only the status line and headers of the response, which
+    /// is all that is sent in reply to a `HEAD` request.
+    pub fn head_bytes(&self) -> Vec<u8> {
+        let mut head = format!("HTTP/1.1 {}\r\n", self.status);
+        for (name, value) in self.headers.iter() {
+            if name.is_empty() || !name.bytes().all(is_token_char) {
+                continue;
+            }
+            let value: String = value
+                .chars()
+                .filter(|ch| !ch.is_ascii_control() || *ch == '\t')
+                .collect();
+            head.push_str(&format!("{name}: {value}\r\n"));
+        }
+        // Informational and `204 No Content` responses never have a body
+        let bodiless = self.status.is_informational() || self.status == StatusCode::NoContent;
+        if !bodiless && !self.is_chunked() && !self.has_header("Content-Length") {
+            head.push_str(&format!("Content-Length: {}\r\n", self.body.len()));
+        }
+        head.push_str("\r\n");
+        head.into_bytes()
+    }
+}
+
+impl From<String> for Response {
+    fn from(body: String) -> Self {
+        Self::ok(body)
+    }
+}
+
+impl From<&str> for Response {
+    fn from(body: &str) -> Self {
+        Self::ok(body)
+    }
+}
+
+/// A query which failed to convert is the fault of the client, so is
+/// answered with a `400 Bad Request` describing what was wrong.
+impl From<&QueryError> for Response {
+    fn from(error: &QueryError) -> Self {
+        Self::new(StatusCode::BadRequest).body(error.to_string())
+    }
+}
+
+/// A request which failed to be read is the fault of the client, answered
+/// with the status for whichever limit it went over if it was too large,
+/// or with `501 Not Implemented` if its body has an encoding we can't
+/// decode.
+impl From<&RequestError> for Response {
+    fn from(error: &RequestError) -> Self {
+        let status = match error {
+            RequestError::RequestLineTooLong => StatusCode::UriTooLong,
+            RequestError::HeadersTooLarge | RequestError::TooManyHeaders => {
+                StatusCode::RequestHeaderFieldsTooLarge
+            }
+            RequestError::BodyTooLarge => StatusCode::ContentTooLarge,
+            RequestError::UnsupportedTransferEncoding => StatusCode::NotImplemented,
+            _ => StatusCode::BadRequest,
+        };
+        Self::new(status).body(status.to_string())
+    }
+}
+
+/// A multipart body which failed to parse is the fault of the client,
+/// answered with a `413 Content Too Large` if it went over its limits.
+impl From<&MultipartError> for Response {
+    fn from(error: &MultipartError) -> Self {
+        let status = match error {
+            MultipartError::Malformed => StatusCode::BadRequest,
+            MultipartError::PartTooLarge | MultipartError::TooLarge => StatusCode::ContentTooLarge,
+        };
+        Self::new(status).body(status.to_string())
+    }
+}
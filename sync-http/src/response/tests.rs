@@ -0,0 +1,168 @@
+use super::*;
+use crate::errors::InvalidStatusCode;
+
+#[test]
+fn empty_response() {
+    let response = Response::default();
+
+    assert_eq!(
+        response.to_bytes(),
+        b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n"
+    );
+}
+
+#[test]
+fn response_with_headers() {
+    let response = Response::new(StatusCode::Created)
+        .header("Content-Type", "text/plain")
+        .header("Cache-Control", "no-cache")
+        .body("created");
+
+    assert_eq!(
+        response.to_bytes(),
+        b"HTTP/1.1 201 Created\r\n\
+            Content-Type: text/plain\r\n\
+            Cache-Control: no-cache\r\n\
+            Content-Length: 7\r\n\r\n\
+            created"
+    );
+}
+
+#[test]
+fn redirect_response() {
+    let response = Response::redirect("/login");
+
+    assert_eq!(response.status, StatusCode::SeeOther);
+    assert!(response.has_header("location"));
+    assert_eq!(
+        response.to_bytes(),
+        b"HTTP/1.1 303 See Other\r\nLocation: /login\r\nContent-Length: 0\r\n\r\n"
+    );
+}
+
+#[test]
+fn header_injection() {
+    let response = Response::redirect("/x\r\nSet-Cookie: a=b")
+        .header("X-Split\r\nSet-Cookie", "a=b")
+        .header("Bad Name", "value");
+
+    assert_eq!(
+        response.to_bytes(),
+        b"HTTP/1.1 303 See Other\r\nLocation: /xSet-Cookie: a=b\r\nContent-Length: 0\r\n\r\n"
+    );
+}
+
+#[test]
+fn request_error_statuses() {
+    const NUM_TESTS: usize = 4;
+    let errors: [RequestError; NUM_TESTS] = [
+        RequestError::BadUri,
+        RequestError::TooManyHeaders,
+        RequestError::BodyTooLarge,
+        RequestError::UnsupportedTransferEncoding,
+    ];
+    let test_vals: [StatusCode; NUM_TESTS] = [
+        StatusCode::BadRequest,
+        StatusCode::RequestHeaderFieldsTooLarge,
+        StatusCode::ContentTooLarge,
+        StatusCode::NotImplemented,
+    ];
+
+    for i in 0..NUM_TESTS {
+        assert_eq!(Response::from(&errors[i]).status, test_vals[i]);
+    }
+}
+
+#[test]
+fn content_length_not_repeated() {
+    let response = Response::ok("abc").header("content-length", "3");
+
+    assert_eq!(
+        response.to_bytes(),
+        b"HTTP/1.1 200 OK\r\ncontent-length: 3\r\n\r\nabc"
+    );
+}
+
+#[test]
+fn chunked_response() {
+    let response = Response::ok("hello").chunked();
+    let empty = Response::new(StatusCode::NoContent);
+
+    assert!(response.is_chunked());
+    assert_eq!(
+        response.to_bytes(),
+        b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhello\r\n0\r\n\r\n"
+    );
+    assert_eq!(
+        response.head_bytes(),
+        b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n"
+    );
+    assert_eq!(empty.to_bytes(), b"HTTP/1.1 204 No Content\r\n\r\n");
+}
+
+#[test]
+fn status_codes() {
+    const NUM_TESTS: usize = 10;
+    let codes: [u16; NUM_TESTS] = [101, 200, 308, 431, 503, 299, 999, 42, 99, 1000];
+    let test_vals: [_; NUM_TESTS] = [
+        Some(StatusCode::SwitchingProtocols),
+        Some(StatusCode::Ok),
+        Some(StatusCode::PermanentRedirect),
+        Some(StatusCode::RequestHeaderFieldsTooLarge),
+        Some(StatusCode::ServiceUnavailable),
+        Some(StatusCode::Unregistered(299)),
+        Some(StatusCode::Unregistered(999)),
+        None,
+        None,
+        None,
+    ];
+
+    for i in 0..NUM_TESTS {
+        let status = StatusCode::from_code(codes[i]);
+        assert_eq!(status, test_vals[i]);
+        if let Some(status) = status {
+            assert_eq!(status.code(), codes[i]);
+        }
+    }
+    assert_eq!(StatusCode::try_from(42), Err(InvalidStatusCode(42)));
+}
+
+#[test]
+fn status_line() {
+    assert_eq!(StatusCode::NotFound.to_string(), "404 Not Found");
+    assert_eq!(
+        StatusCode::HttpVersionNotSupported.to_string(),
+        "505 HTTP Version Not Supported"
+    );
+    assert_eq!(
+        Response::new(StatusCode::Unregistered(299)).to_bytes(),
+        b"HTTP/1.1 299 \r\nContent-Length: 0\r\n\r\n"
+    );
+}
+
+#[test]
+fn status_classes() {
+    let statuses = [
+        StatusCode::Continue,
+        StatusCode::NoContent,
+        StatusCode::Found,
+        StatusCode::Gone,
+        StatusCode::BadGateway,
+        StatusCode::Unregistered(600),
+    ];
+    let classes = statuses.map(|status| {
+        [
+            status.is_informational(),
+            status.is_success(),
+            status.is_redirection(),
+            status.is_client_error(),
+            status.is_server_error(),
+        ]
+    });
+
+    for (i, class) in classes.iter().enumerate() {
+        for (j, is_class) in class.iter().enumerate() {
+            assert_eq!(*is_class, i == j);
+        }
+    }
+}
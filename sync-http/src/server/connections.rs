@@ -0,0 +1,150 @@
+use std::{
+    collections::HashMap,
+    net::{Shutdown, TcpStream},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Condvar, Mutex, MutexGuard,
+    },
+    time::{Duration, Instant},
+};
+
+/// How the open connections were dealt with when a server shut down.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ShutdownReport {
+    /// Connections which were closed with no request left to respond to.
+    pub drained: usize,
+    /// Connections which were forcibly closed at the deadline, while they
+    /// still had a request to respond to.
+    pub cut_off: usize,
+}
+
+/// A connection which is open, and what it is doing.
+struct Open {
+    stream: TcpStream,
+    state: State,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Waiting for a worker since it was accepted, possibly with a request
+    /// already sent.
+    Queued(Instant),
+    /// Waiting for the client to start its next request.
+    Idle,
+    /// Reading or responding to a request.
+    Busy,
+}
+
+/// Every connection the server has open, so that they can be drained when
+/// it shuts down.
+#[derive(Default)]
+pub(crate) struct Connections {
+    open: Mutex<HashMap<u64, Open>>,
+    closed: Condvar,
+    next_id: AtomicU64,
+    draining: AtomicBool,
+}
+
+impl Connections {
+    /// Adds a newly accepted connection, which is queued until a worker
+    /// picks it up. Queued connections may have already sent a request, so
+    /// are waited for like busy ones when draining.
+    pub fn open(&self, stream: TcpStream) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let state = State::Queued(Instant::now());
+        self.lock().insert(id, Open { stream, state });
+        id
+    }
+
+    /// Marks a connection as waiting for its next request, returning `false`
+    /// if the connection should be closed instead as the server is shutting
+    /// down or it has already been closed.
+    pub fn idle(&self, id: u64) -> bool {
+        let mut open = self.lock();
+        match open.get_mut(&id) {
+            Some(connection) if !self.is_draining() => {
+                connection.state = State::Idle;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Marks a connection as having started a request, returning `false` if
+    /// it has been closed.
+    pub fn busy(&self, id: u64) -> bool {
+        let mut open = self.lock();
+        match open.get_mut(&id) {
+            Some(connection) => {
+                connection.state = State::Busy;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn close(&self, id: u64) {
+        if let Some(connection) = self.lock().remove(&id) {
+            let _ = connection.stream.shutdown(Shutdown::Both);
+        }
+        self.closed.notify_all();
+    }
+
+    /// Checks whether any connection has been waiting for a worker for
+    /// longer than the given time, rather than being picked up by a free
+    /// one straight away.
+    pub fn has_waiting(&self, longer_than: Duration) -> bool {
+        self.lock()
+            .values()
+            .any(|connection| match connection.state {
+                State::Queued(since) => since.elapsed() > longer_than,
+                _ => false,
+            })
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::SeqCst)
+    }
+
+    /// Closes every idle connection and waits for the rest to respond to
+    /// their requests, any still open at the deadline are cut off.
+    pub fn drain(&self, deadline: Instant) -> ShutdownReport {
+        self.draining.store(true, Ordering::SeqCst);
+        let mut open = self.lock();
+        let total = open.len();
+        open.retain(|_, connection| {
+            let idle = connection.state == State::Idle;
+            if idle {
+                let _ = connection.stream.shutdown(Shutdown::Both);
+            }
+            !idle
+        });
+
+        while !open.is_empty() {
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            open = self
+                .closed
+                .wait_timeout(open, deadline - now)
+                .unwrap_or_else(|error| error.into_inner())
+                .0;
+        }
+
+        let cut_off = open.len();
+        for (_, connection) in open.drain() {
+            let _ = connection.stream.shutdown(Shutdown::Both);
+        }
+        ShutdownReport {
+            drained: total - cut_off,
+            cut_off,
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<u64, Open>> {
+        // The map is only ever changed by single inserts and removals, so
+        // it is still valid if a thread panicked while holding the lock
+        self.open.lock().unwrap_or_else(|error| error.into_inner())
+    }
+}
@@ -0,0 +1,66 @@
+use super::{stop, ShutdownReport};
+use crate::ServerResult;
+use std::{
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread::JoinHandle,
+};
+
+/// A handle to a server running on its own thread, which can be cloned to
+/// shut the server down from anywhere, including from within a handler.
+#[derive(Debug, Clone)]
+pub struct ServerHandle {
+    running: Arc<AtomicBool>,
+    local_addr: SocketAddr,
+    thread: Arc<Mutex<Option<JoinHandle<ServerResult<ShutdownReport>>>>>,
+}
+
+impl ServerHandle {
+    pub(crate) fn new(
+        running: Arc<AtomicBool>,
+        local_addr: SocketAddr,
+        thread: JoinHandle<ServerResult<ShutdownReport>>,
+    ) -> Self {
+        Self {
+            running,
+            local_addr,
+            thread: Arc::new(Mutex::new(Some(thread))),
+        }
+    }
+
+    /// Stops the server from accepting any more connections, without
+    /// waiting for it to stop.
+    pub fn shutdown(&self) {
+        stop(&self.running, self.local_addr);
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    /// Waits for the server to stop, returning how its open connections
+    /// were drained or the error which stopped it. Only the first call gets
+    /// the result, any others wait for it and then return an empty report.
+    ///
+    /// A handler calling this would be waiting on itself, so handlers
+    /// should only ever call `shutdown`.
+    pub fn join(&self) -> ServerResult<ShutdownReport> {
+        let mut thread = self
+            .thread
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+        match thread.take() {
+            Some(thread) => thread.join().map_err(|_| "Server thread panicked")?,
+            None => Ok(ShutdownReport::default()),
+        }
+    }
+
+    /// The address the server is listening on, such as for finding which
+    /// port was picked when binding to port 0.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+}
@@ -0,0 +1,735 @@
+use crate::{
+    errors::{HandlerPanicked, MultipartError, QueryError, RequestError},
+    request::{Method, Params, Request, RequestLimits, RequestReader},
+    response::{Response, StatusCode},
+    ServerResult,
+};
+use regex::Regex;
+mod connections;
+mod deadline;
+mod handle;
+mod pool;
+mod route;
+pub use connections::*;
+use deadline::*;
+pub use handle::*;
+pub use pool::*;
+pub use route::*;
+
+#[cfg(test)]
+mod tests;
+
+use std::{
+    any::Any,
+    error::Error,
+    io::{self, ErrorKind, Write},
+    net::{Ipv4Addr, Ipv6Addr, Shutdown, SocketAddr, TcpListener, TcpStream},
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::Receiver,
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// How long to wait before accepting again after failing to accept.
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(50);
+
+/// How often a connection waiting for its next request checks whether it
+/// should be closed instead, and how long a connection can wait for a
+/// worker before an idle one is closed for it.
+const IDLE_POLL: Duration = Duration::from_millis(50);
+
+pub trait ServerStream {
+    fn close_response(&self) -> ServerResult<()>;
+    fn write_empty(&mut self) -> ServerResult<()>;
+    fn write_bytes<'a>(&mut self, response: impl Into<&'a [u8]>) -> ServerResult<()>;
+    fn write_response(&mut self, response: &Response) -> ServerResult<()>;
+}
+
+pub type Handler = dyn Fn(Request) -> ServerResult<Response> + Sync + Send;
+pub type HandlerMap = (Method, Regex, &'static Handler);
+pub type ErrorHandler = dyn Fn(&dyn Error) -> Response + Sync + Send;
+
+pub struct ServerBuilder {
+    ip_address: Option<String>,
+    port: Option<u16>,
+    ttl: Option<u32>,
+    shutdown: Option<Receiver<()>>,
+    handlers: Vec<HandlerMap>,
+    not_found: Option<&'static Handler>,
+    on_error: Option<&'static ErrorHandler>,
+    keep_alive: KeepAlive,
+    timeouts: Timeouts,
+    limits: RequestLimits,
+    workers: Workers,
+    shutdown_timeout: Duration,
+}
+
+/// How long persistent connections are kept open for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct KeepAlive {
+    idle_timeout: Duration,
+    max_requests: usize,
+}
+
+impl Default for KeepAlive {
+    fn default() -> Self {
+        Self {
+            idle_timeout: Duration::from_secs(5),
+            max_requests: 100,
+        }
+    }
+}
+
+/// How long a client is given to send each part of a request, and to
+/// receive the response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Timeouts {
+    header: Duration,
+    body: Duration,
+    write: Duration,
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Self {
+            header: Duration::from_secs(10),
+            body: Duration::from_secs(30),
+            write: Duration::from_secs(30),
+        }
+    }
+}
+
+/// The size of the pool of workers which respond to connections.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Workers {
+    count: usize,
+    queue_size: usize,
+    policy: QueuePolicy,
+}
+
+impl Default for Workers {
+    fn default() -> Self {
+        Self {
+            count: 16,
+            queue_size: 64,
+            policy: QueuePolicy::Block,
+        }
+    }
+}
+
+/// Everything a worker needs to respond to the requests on a connection.
+struct Service {
+    handlers: Vec<HandlerMap>,
+    not_found: Option<&'static Handler>,
+    on_error: Option<&'static ErrorHandler>,
+    keep_alive: KeepAlive,
+    timeouts: Timeouts,
+    limits: RequestLimits,
+    connections: Arc<Connections>,
+}
+
+/// An accepted connection, along with its id in the open connections.
+type Connection = (u64, TcpStream);
+
+pub struct Server {
+    listener: TcpListener,
+    running: Arc<AtomicBool>,
+    shutdown: Option<Arc<Mutex<Receiver<()>>>>,
+    shutdown_timeout: Duration,
+    connections: Arc<Connections>,
+    pool: ThreadPool<Connection>,
+}
+
+impl Server {
+    fn new(
+        listener: TcpListener,
+        shutdown: Option<Receiver<()>>,
+        shutdown_timeout: Duration,
+        service: Service,
+        workers: Workers,
+    ) -> Self {
+        let connections = service.connections.clone();
+        let pool = ThreadPool::new(
+            workers.count,
+            workers.queue_size,
+            workers.policy,
+            move |(id, stream)| {
+                // Handlers' panics are caught when they're run, but the
+                // connection still has to be closed if reading panics
+                let served = panic::catch_unwind(AssertUnwindSafe(|| service.serve(id, stream)));
+                if served.is_err() {
+                    service.connections.close(id);
+                }
+            },
+        );
+        Self {
+            listener,
+            running: Arc::new(AtomicBool::new(true)),
+            shutdown: shutdown.map(|shutdown| Arc::new(Mutex::new(shutdown))),
+            shutdown_timeout,
+            connections,
+            pool,
+        }
+    }
+    /// Creates a new ServerBuilder which defaults with values of:
+    /// - ttl: None (time to live)
+    /// - ip_address: 127.0.0.1
+    /// - port: 8080
+    /// - idle_timeout: 5 seconds
+    /// - max_requests: 100
+    /// - header_timeout: 10 seconds
+    /// - body_timeout: 30 seconds
+    /// - write_timeout: 30 seconds
+    /// - limits: `RequestLimits::default()`
+    /// - workers: 16, with up to 64 connections queued for them
+    /// - queue_policy: Block
+    /// - shutdown_timeout: 10 seconds
+    pub fn create() -> ServerBuilder {
+        ServerBuilder {
+            ip_address: None,
+            port: None,
+            ttl: None,
+            shutdown: None,
+            handlers: vec![],
+            not_found: None,
+            on_error: None,
+            keep_alive: KeepAlive::default(),
+            timeouts: Timeouts::default(),
+            limits: RequestLimits::default(),
+            workers: Workers::default(),
+            shutdown_timeout: Duration::from_secs(10),
+        }
+    }
+
+    /// Accepts the next connection, leaving its requests to be read by the
+    /// worker which responds to them.
+    pub fn poll(&mut self) -> ServerResult<TcpStream> {
+        let (stream, _addr) = self.listener.accept()?;
+        Ok(stream)
+    }
+
+    /// Accepts and handles connections until the server is shut down,
+    /// either by its `ServerHandle` or by its shutdown `Receiver`. Open
+    /// connections are then given until the shutdown timeout to finish
+    /// responding, and any left are cut off.
+    pub fn handle_loop(&mut self) -> ServerResult<ShutdownReport> {
+        if let Some(shutdown) = self.shutdown.clone() {
+            let running = self.running.clone();
+            let addr = self.listener.local_addr()?;
+            thread::spawn(move || {
+                let lock = shutdown.lock().unwrap();
+                if lock.recv().is_ok() {
+                    stop(&running, addr);
+                }
+            });
+        }
+        // Accepting blocks, so shutting down connects to wake the loop up
+        while self.running.load(Ordering::SeqCst) {
+            let stream = match self.poll() {
+                Ok(stream) => stream,
+                // Errors such as running out of file descriptors can last
+                // until connections are closed, so wait rather than spin
+                Err(_) => {
+                    thread::sleep(ACCEPT_BACKOFF);
+                    continue;
+                }
+            };
+            // The connection which woke the loop up is dropped
+            if !self.running.load(Ordering::SeqCst) {
+                break;
+            }
+            // A connection which can't be tracked, such as when the process
+            // is out of file descriptors, is dropped rather than stopping
+            // the server without draining the rest
+            let _ = self.handle_connection(stream);
+        }
+
+        let deadline = Instant::now() + self.shutdown_timeout;
+        let report = self.connections.drain(deadline);
+        self.pool.join(Some(deadline));
+        Ok(report)
+    }
+
+    /// Queues the connection to be responded to by the next free worker,
+    /// or responds with `503 Service Unavailable` if the queue is full and
+    /// the server rejects connections when it is.
+    pub fn handle_connection(&self, stream: TcpStream) -> ServerResult<()> {
+        let id = self.connections.open(stream.try_clone()?);
+        if let Err((id, mut stream)) = self.pool.execute((id, stream)) {
+            let response = Response::new(StatusCode::ServiceUnavailable)
+                .header("Connection", "close")
+                .body(StatusCode::ServiceUnavailable.to_string());
+            // The client is turned away either way, so errors are ignored
+            let _ = stream.write_response(&response);
+            self.connections.close(id);
+        }
+        Ok(())
+    }
+}
+
+impl Service {
+    /// Reads and responds to requests on the connection until either side
+    /// closes it, it is idle for too long or it has served too many.
+    /// Pipelined requests are responded to one at a time, in order. A
+    /// client which sends a bad request, or is too slow sending it, is sent
+    /// an error response and the connection is closed.
+    ///
+    /// Once the server is shutting down, connections are closed after their
+    /// current response instead.
+    fn serve(&self, id: u64, mut stream: TcpStream) {
+        let Ok(reader) = stream.try_clone() else {
+            self.connections.close(id);
+            return;
+        };
+        let mut reader = RequestReader::new(DeadlineStream::new(reader)).limits(self.limits);
+        if stream.set_write_timeout(Some(self.timeouts.write)).is_err() {
+            self.connections.close(id);
+            return;
+        }
+
+        let mut served = 0;
+        loop {
+            // Wait for the client to start its next request, unless it has
+            // been pipelined, the first is waited for up to the header timeout
+            let started = if reader.has_buffered() {
+                self.start_request(id)
+            } else if served == 0 {
+                self.wait_request(id, &stream, self.timeouts.header, false)
+            } else {
+                self.wait_request(id, &stream, self.keep_alive.idle_timeout, true)
+            };
+            if let Err(error) = started {
+                // A client which was never responded to is told it was too slow
+                if let Some(response) = read_error(error.as_ref()).filter(|_| served == 0) {
+                    let _ = stream.write_response(&response.header("Connection", "close"));
+                }
+                break;
+            }
+            let request = match self.read_request(&mut reader) {
+                Ok(request) => request,
+                Err(error) => {
+                    if let Some(response) = read_error(error.as_ref()) {
+                        let _ = stream.write_response(&response.header("Connection", "close"));
+                    }
+                    break;
+                }
+            };
+
+            served += 1;
+            // A body framed by both headers may have been framed the other
+            // way by a proxy in front of us, so what follows can't be trusted
+            let ambiguous = request.headers.contains("Content-Length")
+                && request.headers.contains("Transfer-Encoding");
+            let keep_alive =
+                request.keep_alive() && !ambiguous && served < self.keep_alive.max_requests;
+            let head = request.method == Method::Head;
+            let mut response = dispatch(&self.handlers, self.not_found, self.on_error, request);
+            // The server may have started shutting down during the handler
+            let close = !keep_alive || self.connections.is_draining();
+            if close && !response.has_header_token("Connection", "close") {
+                response = response.header("Connection", "close");
+            }
+
+            // Responses to `HEAD` requests describe the body without it
+            let written = if head {
+                stream.write_all(&response.head_bytes()).map_err(Into::into)
+            } else {
+                stream.write_response(&response)
+            };
+            if written.is_err()
+                || response.has_header_token("Connection", "close")
+                || !self.connections.idle(id)
+            {
+                break;
+            }
+        }
+        self.connections.close(id);
+    }
+
+    /// Waits for the client to send the first byte of its next request,
+    /// then marks the connection as busy. Until then the connection is idle,
+    /// so is closed if the server shuts down. A kept alive connection is
+    /// also closed if other connections are left waiting for a worker, so
+    /// that idle clients can't hold every worker until they time out.
+    fn wait_request(
+        &self,
+        id: u64,
+        stream: &TcpStream,
+        timeout: Duration,
+        kept_alive: bool,
+    ) -> ServerResult<()> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                Err(io::Error::from(ErrorKind::TimedOut))?;
+            }
+            // Shutting down is checked for between short waits
+            stream.set_read_timeout(Some(remaining.min(IDLE_POLL)))?;
+            match stream.peek(&mut [0]) {
+                Ok(0) => Err(io::Error::from(ErrorKind::UnexpectedEof))?,
+                Ok(_) => return self.start_request(id),
+                Err(error) if !is_timeout(&error) => Err(error)?,
+                Err(_) => {}
+            }
+            let needed = kept_alive && self.connections.has_waiting(IDLE_POLL);
+            if needed || !self.connections.idle(id) {
+                Err(io::Error::from(ErrorKind::ConnectionAborted))?;
+            }
+        }
+    }
+
+    /// Marks the connection as busy with a request, which fails if the
+    /// connection was closed as the server shut down in the meantime.
+    fn start_request(&self, id: u64) -> ServerResult<()> {
+        if !self.connections.busy(id) {
+            Err(io::Error::from(ErrorKind::ConnectionAborted))?;
+        }
+        Ok(())
+    }
+
+    /// Reads the next request, giving the client until the header timeout
+    /// to send its headers and then until the body timeout for its body.
+    fn read_request(&self, reader: &mut RequestReader<DeadlineStream>) -> ServerResult<Request> {
+        reader.get_mut().timeout(self.timeouts.header);
+        let mut request = reader.read_head()?;
+        reader.get_mut().timeout(self.timeouts.body);
+        reader.read_body(&mut request)?;
+        Ok(request)
+    }
+}
+
+impl ServerStream for TcpStream {
+    fn close_response(&self) -> ServerResult<()> {
+        self.shutdown(Shutdown::Write)?;
+        Ok(())
+    }
+
+    fn write_empty(&mut self) -> ServerResult<()> {
+        self.write_response(&Response::default())
+    }
+
+    fn write_bytes<'a>(&mut self, response: impl Into<&'a [u8]>) -> ServerResult<()> {
+        let response: &[u8] = response.into();
+        self.write_response(&Response::ok(response))
+    }
+
+    fn write_response(&mut self, response: &Response) -> ServerResult<()> {
+        self.write_all(&response.to_bytes())?;
+        Ok(())
+    }
+}
+
+impl ServerBuilder {
+    pub fn ip_address(mut self, ip: String) -> Self {
+        self.ip_address = Some(ip);
+        self
+    }
+
+    pub fn port(mut self, port: u16) -> Self {
+        self.port = Some(port);
+        self
+    }
+
+    pub fn ttl(mut self, ttl: u32) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    pub fn shutdown(mut self, shutdown: Receiver<()>) -> Self {
+        self.shutdown = Some(shutdown);
+        self
+    }
+
+    /// Registers a handler for any method, including extension methods.
+    pub fn route(
+        mut self,
+        method: Method,
+        route: &str,
+        handler: &'static Handler,
+    ) -> ServerResult<Self> {
+        self.handlers.push((method, compile_route(route)?, handler));
+        Ok(self)
+    }
+
+    pub fn get(self, route: &str, handler: &'static Handler) -> ServerResult<Self> {
+        self.route(Method::Get, route, handler)
+    }
+
+    pub fn head(self, route: &str, handler: &'static Handler) -> ServerResult<Self> {
+        self.route(Method::Head, route, handler)
+    }
+
+    pub fn post(self, route: &str, handler: &'static Handler) -> ServerResult<Self> {
+        self.route(Method::Post, route, handler)
+    }
+
+    pub fn put(self, route: &str, handler: &'static Handler) -> ServerResult<Self> {
+        self.route(Method::Put, route, handler)
+    }
+
+    pub fn delete(self, route: &str, handler: &'static Handler) -> ServerResult<Self> {
+        self.route(Method::Delete, route, handler)
+    }
+
+    pub fn connect(self, route: &str, handler: &'static Handler) -> ServerResult<Self> {
+        self.route(Method::Connect, route, handler)
+    }
+
+    pub fn options(self, route: &str, handler: &'static Handler) -> ServerResult<Self> {
+        self.route(Method::Options, route, handler)
+    }
+
+    pub fn trace(self, route: &str, handler: &'static Handler) -> ServerResult<Self> {
+        self.route(Method::Trace, route, handler)
+    }
+
+    pub fn patch(self, route: &str, handler: &'static Handler) -> ServerResult<Self> {
+        self.route(Method::Patch, route, handler)
+    }
+
+    /// Sets the handler used when no other handler matches the request,
+    /// by default a plain `404 Not Found` response is sent.
+    pub fn not_found(mut self, handler: &'static Handler) -> Self {
+        self.not_found = Some(handler);
+        self
+    }
+
+    /// Sets the handler used to render the response for a handler which
+    /// errored or panicked, by default a plain `500 Internal Server Error`
+    /// response is sent.
+    pub fn on_error(mut self, handler: &'static ErrorHandler) -> Self {
+        self.on_error = Some(handler);
+        self
+    }
+
+    /// Sets how long a connection is kept open waiting for another request
+    /// before it is closed.
+    pub fn idle_timeout(mut self, timeout: Duration) -> Self {
+        self.keep_alive.idle_timeout = timeout;
+        self
+    }
+
+    /// Sets the most requests served on a single connection before it is
+    /// closed, a maximum of 1 closes every connection after its response.
+    pub fn max_requests(mut self, max: usize) -> Self {
+        self.keep_alive.max_requests = max.max(1);
+        self
+    }
+
+    /// Sets how long a client is given to send the request line and headers
+    /// of a request, once it has started sending it.
+    pub fn header_timeout(mut self, timeout: Duration) -> Self {
+        self.timeouts.header = timeout;
+        self
+    }
+
+    /// Sets how long a client is given to send the body of a request.
+    pub fn body_timeout(mut self, timeout: Duration) -> Self {
+        self.timeouts.body = timeout;
+        self
+    }
+
+    /// Sets how long writing a response to a client may block for.
+    pub fn write_timeout(mut self, timeout: Duration) -> Self {
+        self.timeouts.write = timeout;
+        self
+    }
+
+    /// Sets the longest request line accepted, longer request lines are
+    /// answered with `414 URI Too Long`.
+    pub fn max_request_line(mut self, length: usize) -> Self {
+        self.limits.request_line = length;
+        self
+    }
+
+    /// Sets the most bytes of headers accepted, more are answered with
+    /// `431 Request Header Fields Too Large`.
+    pub fn max_header_bytes(mut self, bytes: usize) -> Self {
+        self.limits.header_bytes = bytes;
+        self
+    }
+
+    /// Sets the most headers accepted, more are answered with
+    /// `431 Request Header Fields Too Large`.
+    pub fn max_headers(mut self, count: usize) -> Self {
+        self.limits.header_count = count;
+        self
+    }
+
+    /// Sets the largest body accepted, larger bodies are answered with
+    /// `413 Content Too Large` without being read.
+    pub fn max_body_size(mut self, size: usize) -> Self {
+        self.limits.body_size = size;
+        self
+    }
+
+    /// Sets how many worker threads respond to connections, each worker
+    /// responds to one connection at a time.
+    pub fn workers(mut self, workers: usize) -> Self {
+        self.workers.count = workers.max(1);
+        self
+    }
+
+    /// Sets how many accepted connections can wait for a free worker.
+    pub fn queue_size(mut self, size: usize) -> Self {
+        self.workers.queue_size = size;
+        self
+    }
+
+    /// Sets what happens to new connections once the queue is full.
+    pub fn queue_policy(mut self, policy: QueuePolicy) -> Self {
+        self.workers.policy = policy;
+        self
+    }
+
+    /// Sets how long open connections are given to finish responding once
+    /// the server is shut down, before they are cut off.
+    pub fn shutdown_timeout(mut self, timeout: Duration) -> Self {
+        self.shutdown_timeout = timeout;
+        self
+    }
+
+    /// Binds the server and starts it on its own thread, returning a handle
+    /// to shut it down and wait for it with.
+    pub fn bind(self) -> ServerResult<ServerHandle> {
+        let ip = self.ip_address.unwrap_or("127.0.0.1".into());
+        let port = self.port.unwrap_or(8080);
+        let addr = format!("{ip}:{port}");
+        let listener = TcpListener::bind(addr)?;
+
+        if let Some(ttl) = self.ttl {
+            listener.set_ttl(ttl)?;
+        }
+
+        let service = Service {
+            handlers: self.handlers,
+            not_found: self.not_found,
+            on_error: self.on_error,
+            keep_alive: self.keep_alive,
+            timeouts: self.timeouts,
+            limits: self.limits,
+            connections: Arc::default(),
+        };
+        let mut server = Server::new(
+            listener,
+            self.shutdown,
+            self.shutdown_timeout,
+            service,
+            self.workers,
+        );
+        let running = server.running.clone();
+        let local_addr = server.listener.local_addr()?;
+        let thread = thread::spawn(move || server.handle_loop());
+        Ok(ServerHandle::new(running, local_addr, thread))
+    }
+}
+
+/// Finds the response for a request which couldn't be read, if the client
+/// is still there to be sent one.
+fn read_error(error: &(dyn Error + 'static)) -> Option<Response> {
+    if is_timeout(error) {
+        return Some(
+            Response::new(StatusCode::RequestTimeout).body(StatusCode::RequestTimeout.to_string()),
+        );
+    }
+    match error.downcast_ref::<RequestError>()? {
+        RequestError::Incomplete => None,
+        error => Some(error.into()),
+    }
+}
+
+/// Stops the server listening on the address, connecting to it so that it
+/// notices without waiting for another client.
+pub(crate) fn stop(running: &AtomicBool, addr: SocketAddr) {
+    if !running.swap(false, Ordering::SeqCst) {
+        return;
+    }
+    let mut addr = addr;
+    // A server listening on every address can be reached on loopback
+    if addr.ip().is_unspecified() {
+        match addr {
+            SocketAddr::V4(_) => addr.set_ip(Ipv4Addr::LOCALHOST.into()),
+            SocketAddr::V6(_) => addr.set_ip(Ipv6Addr::LOCALHOST.into()),
+        }
+    }
+    let _ = TcpStream::connect_timeout(&addr, Duration::from_secs(1));
+}
+
+/// Finds the handler for the request and runs it, falling back to the
+/// not found handler. `HEAD` requests without a handler of their own are
+/// handled like `GET` requests, as RFC 9110 requires.
+fn dispatch(
+    handlers: &[HandlerMap],
+    not_found: Option<&'static Handler>,
+    on_error: Option<&'static ErrorHandler>,
+    mut request: Request,
+) -> Response {
+    let find = |method: &Method| {
+        handlers
+            .iter()
+            .filter(|(handles, _, _)| handles == method)
+            .find_map(|(_, path, handler)| {
+                let captures = path.captures(request.uri.as_str())?;
+                Some((*handler, Params::from_captures(path, &captures)))
+            })
+    };
+    let handler = find(&request.method)
+        .or_else(|| (request.method == Method::Head).then(|| find(&Method::Get))?)
+        .or(not_found.map(|handler| (handler, Params::default())));
+    match handler {
+        Some((handler, params)) => {
+            request.params = params;
+            run_handler(|| handler(request), on_error)
+        }
+        None => Response::new(StatusCode::NotFound).body(StatusCode::NotFound.to_string()),
+    }
+}
+
+/// Runs a handler, turning any error or panic into a response so that
+/// the client is never left without one.
+fn run_handler(
+    handler: impl FnOnce() -> ServerResult<Response>,
+    on_error: Option<&'static ErrorHandler>,
+) -> Response {
+    let error = match panic::catch_unwind(AssertUnwindSafe(handler)) {
+        Ok(Ok(response)) => return response,
+        Ok(Err(error)) => match client_error(error.as_ref()) {
+            Some(response) => return response,
+            None => error,
+        },
+        Err(panic) => Box::new(HandlerPanicked(panic_message(panic))),
+    };
+    on_error
+        .and_then(|on_error| {
+            panic::catch_unwind(AssertUnwindSafe(|| on_error(error.as_ref()))).ok()
+        })
+        .unwrap_or_else(|| {
+            Response::new(StatusCode::InternalServerError)
+                .body(StatusCode::InternalServerError.to_string())
+        })
+}
+
+/// Finds the response for errors caused by a bad request from the client,
+/// rather than by the handler.
+fn client_error(error: &(dyn Error + 'static)) -> Option<Response> {
+    if let Some(error) = error.downcast_ref::<QueryError>() {
+        Some(error.into())
+    } else {
+        error.downcast_ref::<MultipartError>().map(Response::from)
+    }
+}
+
+fn panic_message(panic: Box<dyn Any + Send>) -> String {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::new()
+    }
+}
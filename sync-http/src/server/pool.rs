@@ -0,0 +1,109 @@
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        mpsc::{self, Receiver, SyncSender, TrySendError},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+/// What to do with a new connection when every worker is busy and the
+/// queue of waiting connections is full.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum QueuePolicy {
+    /// Wait for space in the queue before accepting more connections.
+    #[default]
+    Block,
+    /// Respond with `503 Service Unavailable` and close the connection.
+    Reject,
+}
+
+/// A fixed number of worker threads, which each take jobs off of a bounded
+/// queue and pass them to the same handler. A job which panics is dropped,
+/// and its worker moves on to the next.
+pub(crate) struct ThreadPool<T> {
+    sender: Option<SyncSender<T>>,
+    workers: Vec<JoinHandle<()>>,
+    policy: QueuePolicy,
+}
+
+impl<T: Send + 'static> ThreadPool<T> {
+    pub fn new(
+        workers: usize,
+        queue_size: usize,
+        policy: QueuePolicy,
+        handler: impl Fn(T) + Send + Sync + 'static,
+    ) -> Self {
+        let (sender, receiver) = mpsc::sync_channel(queue_size);
+        let receiver = Arc::new(Mutex::new(receiver));
+        let handler = Arc::new(handler);
+        let workers = (0..workers.max(1))
+            .map(|_| {
+                let receiver = receiver.clone();
+                let handler = handler.clone();
+                thread::spawn(move || {
+                    while let Some(job) = next_job(&receiver) {
+                        // A panicking job mustn't take its worker down with it
+                        let _ = panic::catch_unwind(AssertUnwindSafe(|| handler(job)));
+                    }
+                })
+            })
+            .collect();
+
+        Self {
+            sender: Some(sender),
+            workers,
+            policy,
+        }
+    }
+
+    /// Queues a job for the next free worker, if the queue is full then
+    /// this either waits for space or gives the job back, depending on the
+    /// policy.
+    pub fn execute(&self, job: T) -> Result<(), T> {
+        let Some(sender) = self.sender.as_ref() else {
+            return Err(job);
+        };
+        match self.policy {
+            QueuePolicy::Block => sender.send(job).map_err(|error| error.0),
+            QueuePolicy::Reject => sender.try_send(job).map_err(|error| match error {
+                TrySendError::Full(job) | TrySendError::Disconnected(job) => job,
+            }),
+        }
+    }
+}
+
+impl<T> ThreadPool<T> {
+    /// Lets the workers finish every queued job, then waits for them until
+    /// the deadline, after which any still working are left to finish on
+    /// their own.
+    pub fn join(&mut self, deadline: Option<Instant>) {
+        self.sender.take();
+        for worker in self.workers.drain(..) {
+            if let Some(deadline) = deadline {
+                // Threads can't be joined with a timeout, so are polled
+                while !worker.is_finished() && Instant::now() < deadline {
+                    thread::sleep(Duration::from_millis(10));
+                }
+                if !worker.is_finished() {
+                    continue;
+                }
+            }
+            let _ = worker.join();
+        }
+    }
+}
+
+impl<T> Drop for ThreadPool<T> {
+    fn drop(&mut self) {
+        self.join(None);
+    }
+}
+
+/// Waits for the next job, which is `None` once the pool is dropped.
+fn next_job<T>(receiver: &Mutex<Receiver<T>>) -> Option<T> {
+    // The lock guards no state of its own, so poisoning can be ignored
+    let receiver = receiver.lock().unwrap_or_else(|error| error.into_inner());
+    receiver.recv().ok()
+}
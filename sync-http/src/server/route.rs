@@ -0,0 +1,44 @@
+use crate::{errors::FailedToCompileRoute, ServerResult};
+use regex::Regex;
+
+/// The characters a `*` wildcard segment will match.
+const WILDCARD: &str = r"[A-Za-z0-9\-_~.]*";
+
+/// Compiles a route into a regex matching against sanitised uris, where
+/// a route's segments may be:
+/// - `:name` to capture a single segment into the parameter `name`
+/// - `*name` as the last segment to capture the rest of the uri
+/// - `*` anywhere in a segment as a wildcard within that segment
+pub fn compile_route(route: &str) -> ServerResult<Regex> {
+    let segments: Vec<&str> = route.split('/').filter(|part| !part.is_empty()).collect();
+    let mut pattern = String::new();
+    for (i, segment) in segments.iter().enumerate() {
+        let separator = if i == 0 { "" } else { r"\/" };
+        if let Some(name) = segment.strip_prefix(':') {
+            if !is_param_name(name) {
+                Err(FailedToCompileRoute)?;
+            }
+            pattern.push_str(&format!("{separator}(?P<{name}>[^/]+)"));
+        } else if let Some(name) = segment.strip_prefix('*').filter(|name| is_param_name(name)) {
+            if i + 1 != segments.len() {
+                Err(FailedToCompileRoute)?;
+            }
+            // The rest of the uri may be empty, so the separator is optional
+            pattern.push_str(&format!("(?:{separator}(?P<{name}>.*))?"));
+        } else {
+            let segment = regex::escape(segment).replace(r"\*", WILDCARD);
+            pattern.push_str(&format!("{separator}{segment}"));
+        }
+    }
+    match Regex::new(&format!("^{pattern}$")) {
+        Ok(re_route) => Ok(re_route),
+        Err(_) => Err(FailedToCompileRoute)?,
+    }
+}
+
+fn is_param_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+}
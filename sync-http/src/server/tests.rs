@@ -0,0 +1,652 @@
+use super::*;
+use crate::request::Params;
+use std::{
+    io::Read,
+    net::SocketAddr,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+fn route_params(route: &str, uri: &str) -> Option<Params> {
+    let route = compile_route(route).unwrap();
+    let captures = route.captures(uri)?;
+    Some(Params::from_captures(&route, &captures))
+}
+
+#[test]
+fn wildcard_routes() {
+    const NUM_TESTS: usize = 6;
+    let routes: [(&str, &str); NUM_TESTS] = [
+        ("*", ""),
+        ("*", "index.html"),
+        ("*", "users/1"),
+        ("close", "close"),
+        ("/files/*.txt", "files/notes.txt"),
+        ("/files/*.txt", "files/notes.png"),
+    ];
+    let test_vals: [bool; NUM_TESTS] = [true, true, false, true, true, false];
+
+    for i in 0..NUM_TESTS {
+        let (route, uri) = routes[i];
+        assert_eq!(route_params(route, uri).is_some(), test_vals[i]);
+    }
+}
+
+#[test]
+fn named_params() {
+    let params = route_params("/users/:id/posts/:post_id", "users/12/posts/first").unwrap();
+
+    assert_eq!(params.get("id"), Some("12"));
+    assert_eq!(params.get_as::<u32>("id"), Some(12));
+    assert_eq!(params.get("post_id"), Some("first"));
+    assert_eq!(params.get_as::<u32>("post_id"), None);
+    assert!(!params.contains("name"));
+    assert_eq!(route_params("/users/:id", "users"), None);
+    assert_eq!(route_params("/users/:id", "users/12/posts"), None);
+}
+
+#[test]
+fn catch_all_params() {
+    const NUM_TESTS: usize = 3;
+    let uris: [&str; NUM_TESTS] = ["static/css/main.css", "static/logo.png", "static"];
+    let test_vals: [&str; NUM_TESTS] = ["css/main.css", "logo.png", ""];
+
+    for i in 0..NUM_TESTS {
+        let params = route_params("/static/*rest", uris[i]).unwrap();
+        assert_eq!(params.get("rest"), Some(test_vals[i]));
+    }
+    assert_eq!(route_params("/static/*rest", "assets/logo.png"), None);
+}
+
+#[test]
+fn invalid_routes() {
+    assert!(compile_route("/static/*rest/more").is_err());
+    assert!(compile_route("/users/:").is_err());
+    assert!(compile_route("/users/:bad-name").is_err());
+}
+
+#[test]
+fn query_errors_are_bad_requests() {
+    let response = run_handler(
+        || Err(QueryError::Missing("page".into()))?,
+        Some(&|_| Response::new(StatusCode::ImUsed)),
+    );
+
+    assert_eq!(response.status, StatusCode::BadRequest);
+    assert_eq!(response.body, b"Missing query parameter `page`");
+}
+
+#[test]
+fn handler_errors() {
+    let errored = run_handler(|| Err("Failed")?, None);
+    let panicked = run_handler(|| panic!("Failed"), None);
+    let rendered = run_handler(
+        || panic!("Failed"),
+        Some(&|error| Response::new(StatusCode::ServiceUnavailable).body(error.to_string())),
+    );
+
+    assert_eq!(errored.status, StatusCode::InternalServerError);
+    assert_eq!(panicked.status, StatusCode::InternalServerError);
+    assert_eq!(rendered.status, StatusCode::ServiceUnavailable);
+    assert_eq!(rendered.body, b"Handler panicked: Failed");
+}
+
+/// Starts a server on any free port, returning it with a client connected
+/// to it.
+fn connect(handlers: Vec<HandlerMap>, keep_alive: KeepAlive) -> (Server, TcpStream) {
+    connect_with_workers(handlers, keep_alive, Workers::default())
+}
+
+fn connect_with_workers(
+    handlers: Vec<HandlerMap>,
+    keep_alive: KeepAlive,
+    workers: Workers,
+) -> (Server, TcpStream) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let service = Service {
+        handlers,
+        not_found: None,
+        on_error: None,
+        keep_alive,
+        timeouts: Timeouts::default(),
+        limits: RequestLimits::default(),
+        connections: Arc::default(),
+    };
+    let server = Server::new(listener, None, Duration::ZERO, service, workers);
+    (server, client)
+}
+
+/// Reads the status line and headers of a response.
+fn read_head(stream: &mut TcpStream) -> String {
+    let mut head = vec![];
+    let mut byte = [0];
+    while !head.ends_with(b"\r\n\r\n") {
+        stream.read_exact(&mut byte).unwrap();
+        head.push(byte[0]);
+    }
+    String::from_utf8(head).unwrap()
+}
+
+/// Reads a single response framed by its `Content-Length`.
+fn read_response(stream: &mut TcpStream) -> String {
+    let head = read_head(stream);
+    let length = head
+        .lines()
+        .find_map(|line| line.strip_prefix("Content-Length: "))
+        .map_or(0, |length| length.parse().unwrap());
+    let mut body = vec![0; length];
+    stream.read_exact(&mut body).unwrap();
+    head + &String::from_utf8(body).unwrap()
+}
+
+#[test]
+fn keep_alive_connections() {
+    let handlers = vec![(
+        Method::Get,
+        compile_route("/").unwrap(),
+        &(|_| Ok("hello".into())) as &'static Handler,
+    )];
+    let keep_alive = KeepAlive {
+        idle_timeout: Duration::from_secs(5),
+        max_requests: 3,
+    };
+    let (mut server, mut client) = connect(handlers, keep_alive);
+
+    client.write_all(b"GET / HTTP/1.1\r\n\r\n").unwrap();
+    let stream = server.poll().unwrap();
+    server.handle_connection(stream).unwrap();
+    assert_eq!(
+        read_response(&mut client),
+        "HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhello"
+    );
+
+    // HEAD requests are handled by the GET handler, and their responses
+    // have no body but keep its length
+    client.write_all(b"HEAD / HTTP/1.1\r\n\r\n").unwrap();
+    assert_eq!(
+        read_head(&mut client),
+        "HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\n"
+    );
+
+    // The connection is closed after the most requests it can serve
+    client.write_all(b"GET / HTTP/1.1\r\n\r\n").unwrap();
+    let mut rest = String::new();
+    client.read_to_string(&mut rest).unwrap();
+    assert_eq!(
+        rest,
+        "HTTP/1.1 200 OK\r\nConnection: close\r\nContent-Length: 5\r\n\r\nhello"
+    );
+}
+
+#[test]
+fn connection_close() {
+    let (mut server, mut client) = connect(vec![], KeepAlive::default());
+
+    client
+        .write_all(b"GET / HTTP/1.1\r\nConnection: close\r\n\r\n")
+        .unwrap();
+    let stream = server.poll().unwrap();
+    server.handle_connection(stream).unwrap();
+    let mut response = String::new();
+    client.read_to_string(&mut response).unwrap();
+    assert_eq!(
+        response,
+        "HTTP/1.1 404 Not Found\r\nConnection: close\r\nContent-Length: 13\r\n\r\n404 Not Found"
+    );
+}
+
+#[test]
+fn pipelined_requests() {
+    let handlers = vec![(
+        Method::Get,
+        compile_route("/*path").unwrap(),
+        &(|request: Request| Ok(request.uri.into())) as &'static Handler,
+    )];
+    let (mut server, mut client) = connect(handlers, KeepAlive::default());
+
+    // Every request is sent before any response is read
+    client
+        .write_all(
+            b"GET /first HTTP/1.1\r\n\r\n\
+            POST /second HTTP/1.1\r\nContent-Length: 4\r\n\r\nbody\
+            GET /third HTTP/1.1\r\nConnection: close\r\n\r\n",
+        )
+        .unwrap();
+    let stream = server.poll().unwrap();
+    server.handle_connection(stream).unwrap();
+    let mut responses = String::new();
+    client.read_to_string(&mut responses).unwrap();
+    assert_eq!(
+        responses,
+        "HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nfirst\
+        HTTP/1.1 404 Not Found\r\nContent-Length: 13\r\n\r\n404 Not Found\
+        HTTP/1.1 200 OK\r\nConnection: close\r\nContent-Length: 5\r\n\r\nthird"
+    );
+}
+
+#[test]
+fn ambiguous_body_length() {
+    let handlers = vec![(
+        Method::Post,
+        compile_route("/*path").unwrap(),
+        &(|request: Request| Ok(request.uri.into())) as &'static Handler,
+    )];
+    let (mut server, mut client) = connect(handlers, KeepAlive::default());
+
+    // The body is read as chunked, but the connection is closed after it
+    // rather than reading the smuggled request
+    client
+        .write_all(
+            b"POST /first HTTP/1.1\r\nContent-Length: 5\r\nTransfer-Encoding: chunked\r\n\r\n\
+            0\r\n\r\n\
+            POST /smuggled HTTP/1.1\r\n\r\n",
+        )
+        .unwrap();
+    let stream = server.poll().unwrap();
+    server.handle_connection(stream).unwrap();
+    let mut responses = String::new();
+    client.read_to_string(&mut responses).unwrap();
+    assert_eq!(
+        responses,
+        "HTTP/1.1 200 OK\r\nConnection: close\r\nContent-Length: 5\r\n\r\nfirst"
+    );
+}
+
+#[test]
+fn idle_connections_free_workers() {
+    let server = Server::create()
+        .port(0)
+        .get("/", &|_| Ok("hello".into()))
+        .unwrap()
+        .workers(2)
+        .idle_timeout(Duration::from_secs(5))
+        .bind()
+        .unwrap();
+    let addr = server.local_addr();
+
+    // Every worker is held by a kept alive connection waiting for more
+    let mut idle = [(); 2].map(|_| TcpStream::connect(addr).unwrap());
+    for client in idle.iter_mut() {
+        client.write_all(b"GET / HTTP/1.1\r\n\r\n").unwrap();
+        read_response(client);
+    }
+
+    // Another client still gets a response well before the idle timeout,
+    // as one of the idle connections is closed to make way for it
+    let start = Instant::now();
+    assert!(get(addr, "/").ends_with("hello"));
+    assert!(start.elapsed() < Duration::from_secs(1));
+    let closed = idle.iter().filter(|client| {
+        client
+            .set_read_timeout(Some(Duration::from_millis(100)))
+            .unwrap();
+        matches!(client.peek(&mut [0]), Ok(0))
+    });
+    assert!(closed.count() >= 1);
+
+    server.shutdown();
+    server.join().unwrap();
+}
+
+#[test]
+fn thread_pool_runs_every_job() {
+    let count = Arc::new(AtomicUsize::new(0));
+    let counted = count.clone();
+    let pool = ThreadPool::new(4, 2, QueuePolicy::Block, move |n: usize| {
+        counted.fetch_add(n, Ordering::SeqCst);
+    });
+    for n in 1..=10 {
+        pool.execute(n).unwrap();
+    }
+
+    // Dropping the pool waits for the queued jobs to finish
+    drop(pool);
+    assert_eq!(count.load(Ordering::SeqCst), 55);
+}
+
+#[test]
+fn thread_pool_survives_panics() {
+    let count = Arc::new(AtomicUsize::new(0));
+    let counted = count.clone();
+    let pool = ThreadPool::new(1, 2, QueuePolicy::Block, move |n: usize| {
+        if n.is_multiple_of(2) {
+            panic!("even job");
+        }
+        counted.fetch_add(n, Ordering::SeqCst);
+    });
+    for n in 1..=10 {
+        pool.execute(n).unwrap();
+    }
+
+    // The only worker carries on with the odd jobs after each panic
+    drop(pool);
+    assert_eq!(count.load(Ordering::SeqCst), 25);
+}
+
+#[test]
+fn full_queue_rejects_connections() {
+    let workers = Workers {
+        count: 1,
+        queue_size: 1,
+        policy: QueuePolicy::Reject,
+    };
+    let (mut server, mut busy) = connect_with_workers(vec![], KeepAlive::default(), workers);
+    let addr = server.listener.local_addr().unwrap();
+
+    // The only worker is kept busy by a connection which is kept alive
+    busy.write_all(b"GET / HTTP/1.1\r\n\r\n").unwrap();
+    let stream = server.poll().unwrap();
+    server.handle_connection(stream).unwrap();
+    read_response(&mut busy);
+
+    // So the next connection fills the queue, and the one after is rejected
+    let mut queued = TcpStream::connect(addr).unwrap();
+    queued.write_all(b"GET / HTTP/1.1\r\n\r\n").unwrap();
+    let stream = server.poll().unwrap();
+    server.handle_connection(stream).unwrap();
+
+    let mut rejected = TcpStream::connect(addr).unwrap();
+    rejected.write_all(b"GET / HTTP/1.1\r\n\r\n").unwrap();
+    let stream = server.poll().unwrap();
+    server.handle_connection(stream).unwrap();
+    let mut response = String::new();
+    rejected.read_to_string(&mut response).unwrap();
+    assert!(response.starts_with("HTTP/1.1 503 Service Unavailable\r\nConnection: close\r\n"));
+
+    // Closing the connections lets the worker finish before it is joined
+    drop(busy);
+    drop(queued);
+}
+
+fn get(addr: SocketAddr, uri: &str) -> String {
+    let mut client = TcpStream::connect(addr).unwrap();
+    client
+        .write_all(format!("GET {uri} HTTP/1.1\r\nConnection: close\r\n\r\n").as_bytes())
+        .unwrap();
+    let mut response = String::new();
+    client.read_to_string(&mut response).unwrap();
+    response
+}
+
+#[test]
+fn custom_not_found() {
+    let server = Server::create()
+        .port(0)
+        .get("/", &|_| Ok("home".into()))
+        .unwrap()
+        .not_found(&|request| {
+            Ok(Response::new(StatusCode::NotFound).body(format!("Nothing at /{}", request.uri)))
+        })
+        .bind()
+        .unwrap();
+    let addr = server.local_addr();
+
+    const NUM_TESTS: usize = 3;
+    let uris: [&str; NUM_TESTS] = ["/", "/missing", "/missing/page"];
+    let test_vals: [&str; NUM_TESTS] = [
+        "HTTP/1.1 200 OK\r\nConnection: close\r\nContent-Length: 4\r\n\r\nhome",
+        "HTTP/1.1 404 Not Found\r\nConnection: close\r\nContent-Length: 19\r\n\r\nNothing at /missing",
+        "HTTP/1.1 404 Not Found\r\nConnection: close\r\nContent-Length: 24\r\n\r\nNothing at /missing/page",
+    ];
+
+    for i in 0..NUM_TESTS {
+        assert_eq!(get(addr, uris[i]), test_vals[i]);
+    }
+
+    server.shutdown();
+    server.join().unwrap();
+}
+
+#[test]
+fn server_handles() {
+    let first = Server::create()
+        .port(0)
+        .get("/", &|_| Ok("first".into()))
+        .unwrap()
+        .bind()
+        .unwrap();
+    let second = Server::create()
+        .port(0)
+        .get("/", &|_| Ok("second".into()))
+        .unwrap()
+        .bind()
+        .unwrap();
+    let addr = first.local_addr();
+
+    // Shutting one server down leaves any others running
+    first.shutdown();
+    first.join().unwrap();
+    assert!(!first.is_running());
+    assert!(second.is_running());
+    assert!(get(second.local_addr(), "/").ends_with("second"));
+
+    // Its address is freed, so a server can be started on it again
+    let restarted = Server::create()
+        .port(addr.port())
+        .get("/", &|_| Ok("restarted".into()))
+        .unwrap()
+        .bind()
+        .unwrap();
+    assert!(get(addr, "/").ends_with("restarted"));
+
+    for server in [second, restarted] {
+        server.shutdown();
+        server.join().unwrap();
+    }
+}
+
+fn sleep(request: Request) -> ServerResult<Response> {
+    let millis = request.params.get_as("millis").unwrap_or_default();
+    thread::sleep(Duration::from_millis(millis));
+    Ok("slept".into())
+}
+
+#[test]
+fn graceful_shutdown() {
+    let server = Server::create()
+        .port(0)
+        .get("/sleep/:millis", &sleep)
+        .unwrap()
+        .shutdown_timeout(Duration::from_secs(5))
+        .bind()
+        .unwrap();
+    let addr = server.local_addr();
+
+    // An idle connection is closed straight away, without a response
+    let mut idle = TcpStream::connect(addr).unwrap();
+    idle.write_all(b"GET /sleep/0 HTTP/1.1\r\n\r\n").unwrap();
+    read_response(&mut idle);
+
+    // While one with a request is let finish, then closed
+    let mut busy = TcpStream::connect(addr).unwrap();
+    busy.write_all(b"GET /sleep/300 HTTP/1.1\r\n\r\n").unwrap();
+    thread::sleep(Duration::from_millis(100));
+
+    server.shutdown();
+    let report = server.join().unwrap();
+    assert_eq!(
+        report,
+        ShutdownReport {
+            drained: 2,
+            cut_off: 0
+        }
+    );
+    let mut response = String::new();
+    busy.read_to_string(&mut response).unwrap();
+    assert_eq!(
+        response,
+        "HTTP/1.1 200 OK\r\nConnection: close\r\nContent-Length: 5\r\n\r\nslept"
+    );
+    assert_eq!(idle.read(&mut [0]).unwrap(), 0);
+}
+
+#[test]
+fn shutdown_deadline() {
+    let server = Server::create()
+        .port(0)
+        .get("/sleep/:millis", &sleep)
+        .unwrap()
+        .shutdown_timeout(Duration::from_millis(100))
+        .bind()
+        .unwrap();
+
+    let mut client = TcpStream::connect(server.local_addr()).unwrap();
+    client
+        .write_all(b"GET /sleep/2000 HTTP/1.1\r\n\r\n")
+        .unwrap();
+    thread::sleep(Duration::from_millis(100));
+
+    // The request is still being responded to at the deadline, so is cut off
+    server.shutdown();
+    let report = server.join().unwrap();
+    assert_eq!(
+        report,
+        ShutdownReport {
+            drained: 0,
+            cut_off: 1
+        }
+    );
+    let mut response = vec![];
+    let _ = client.read_to_end(&mut response);
+    assert!(response.is_empty());
+}
+
+#[test]
+fn queued_shutdown() {
+    let server = Server::create()
+        .port(0)
+        .get("/sleep/:millis", &sleep)
+        .unwrap()
+        .workers(1)
+        .shutdown_timeout(Duration::from_secs(1))
+        .bind()
+        .unwrap();
+    let addr = server.local_addr();
+
+    // The only worker is busy, so the other connections are left queued
+    let mut busy = TcpStream::connect(addr).unwrap();
+    busy.write_all(b"GET /sleep/300 HTTP/1.1\r\n\r\n").unwrap();
+    thread::sleep(Duration::from_millis(50));
+    let mut queued = TcpStream::connect(addr).unwrap();
+    queued.write_all(b"GET /sleep/0 HTTP/1.1\r\n\r\n").unwrap();
+    let mut partial = TcpStream::connect(addr).unwrap();
+    partial.write_all(b"GET /sleep/0 HTTP/1.1\r\n").unwrap();
+    thread::sleep(Duration::from_millis(50));
+
+    // Queued requests are still responded to, while the one which never
+    // finishes its headers is cut off
+    server.shutdown();
+    let report = server.join().unwrap();
+    assert_eq!(
+        report,
+        ShutdownReport {
+            drained: 2,
+            cut_off: 1
+        }
+    );
+    let slept = "HTTP/1.1 200 OK\r\nConnection: close\r\nContent-Length: 5\r\n\r\nslept";
+    for client in [&mut busy, &mut queued] {
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        assert_eq!(response, slept);
+    }
+    let mut response = vec![];
+    let _ = partial.read_to_end(&mut response);
+    assert!(response.is_empty());
+}
+
+#[test]
+fn shutdown_receiver() {
+    let (send, recv) = std::sync::mpsc::channel();
+    let server = Server::create().port(0).shutdown(recv).bind().unwrap();
+
+    // The blocked accept loop is woken up, rather than waiting for a client
+    thread::sleep(Duration::from_millis(50));
+    send.send(()).unwrap();
+    let start = Instant::now();
+    server.join().unwrap();
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert!(!server.is_running());
+}
+
+#[test]
+fn slow_clients() {
+    let server = Server::create()
+        .port(0)
+        .get("/", &|_| Ok("hello".into()))
+        .unwrap()
+        .header_timeout(Duration::from_millis(200))
+        .bind()
+        .unwrap();
+    let addr = server.local_addr();
+
+    // A client which never finishes its request doesn't hold up any others
+    let mut silent = TcpStream::connect(addr).unwrap();
+    silent.write_all(b"GET / HTT").unwrap();
+    assert!(get(addr, "/").ends_with("hello"));
+
+    // Trickling the request in doesn't get around the timeout either
+    let mut trickle = TcpStream::connect(addr).unwrap();
+    let start = Instant::now();
+    for byte in b"GET / HTTP/1.1\r\n" {
+        if trickle.write_all(&[*byte]).is_err() {
+            break;
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+    let timed_out = "HTTP/1.1 408 Request Timeout\r\nConnection: close\r\n";
+    for client in [&mut silent, &mut trickle] {
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with(timed_out));
+    }
+    assert!(start.elapsed() < Duration::from_secs(1));
+
+    server.shutdown();
+    server.join().unwrap();
+}
+
+#[test]
+fn request_limits() {
+    let server = Server::create()
+        .port(0)
+        .post("/", &|_| Ok("accepted".into()))
+        .unwrap()
+        .max_request_line(32)
+        .max_header_bytes(64)
+        .max_headers(2)
+        .max_body_size(8)
+        .bind()
+        .unwrap();
+    let addr = server.local_addr();
+
+    const NUM_TESTS: usize = 6;
+    let requests: [String; NUM_TESTS] = [
+        "POST / HTTP/1.1\r\nA: 1\r\nContent-Length: 8\r\n\r\n12345678".into(),
+        format!("POST /{} HTTP/1.1\r\n\r\n", "a".repeat(32)),
+        format!("POST / HTTP/1.1\r\nA: {}\r\n\r\n", "a".repeat(64)),
+        "POST / HTTP/1.1\r\nA: 1\r\nB: 2\r\nC: 3\r\n\r\n".into(),
+        "POST / HTTP/1.1\r\nContent-Length: 9\r\n\r\n123456789".into(),
+        "POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n5\r\n12345\r\n5\r\n".into(),
+    ];
+    let test_vals: [&str; NUM_TESTS] = [
+        "HTTP/1.1 200 OK\r\n",
+        "HTTP/1.1 414 URI Too Long\r\n",
+        "HTTP/1.1 431 Request Header Fields Too Large\r\n",
+        "HTTP/1.1 431 Request Header Fields Too Large\r\n",
+        "HTTP/1.1 413 Content Too Large\r\n",
+        "HTTP/1.1 413 Content Too Large\r\n",
+    ];
+
+    for i in 0..NUM_TESTS {
+        let mut client = TcpStream::connect(addr).unwrap();
+        client.write_all(requests[i].as_bytes()).unwrap();
+        client.shutdown(Shutdown::Write).unwrap();
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with(test_vals[i]), "{response}");
+    }
+
+    server.shutdown();
+    server.join().unwrap();
+}